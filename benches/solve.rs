@@ -0,0 +1,53 @@
+#![allow(clippy::needless_return)]
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use robinson_mmodules::Robin;
+
+const SIZES: [usize; 7] = [156, 312, 625, 1250, 2500, 5000, 10000];
+
+/// Robinson matrice of size n whose points were shuffled
+fn get_shuffled_robinson_matrice(n: usize, rng: &mut StdRng) -> Vec<Vec<u32>> {
+    let mut robinson = vec![vec![0; n]; n];
+    for decal in 1..n {
+        for i in 0..n - decal {
+            let j = i + decal;
+            let base_value = robinson[i + 1][j].max(robinson[i][j - 1]).max(1);
+            robinson[i][j] = base_value + rng.gen_bool(0.05) as u32;
+        }
+    }
+
+    let mut hidden_order: Vec<usize> = (0..n).collect();
+    hidden_order.shuffle(rng);
+
+    let mut matrice = vec![vec![0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let (x, y) = (hidden_order[i], hidden_order[j]);
+            matrice[x.min(y)][x.max(y)] = robinson[i][j];
+        }
+    }
+    return matrice;
+}
+
+/// Times `solve` for each size, the time should be multiplied by about 4 each time n doubles
+fn bench_solve(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(10);
+
+    for n in SIZES {
+        let robin = Robin::new(get_shuffled_robinson_matrice(n, &mut rng));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &robin, |b, robin| {
+            b.iter(|| robin.solve().is_ok())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
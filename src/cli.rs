@@ -0,0 +1,241 @@
+use std::path::{Path, PathBuf};
+
+use robinson_mmodules::{CsvFormat, PhylipFormat, Triangle};
+
+pub const USAGE: &str = "\
+Usage: robinson <COMMAND> [OPTIONS] [FILE]
+
+Commands:
+  check        Tell whether the matrice of FILE admits a compatible order
+  solve        Print a compatible order of the matrice, one point per line
+  reorder      Print the matrice reordered along a compatible order
+
+FILE is read from the standard input if missing or `-`.
+
+Options:
+  -f, --format <csv|tsv|phylip|npy>  Format of FILE, guessed from its extension, csv by default
+      --header                       The first line of the CSV file holds the labels of the points
+      --row-labels                   The first field of each CSV line is the label of its point
+      --triangle <full|upper|lower>  Part of the matrice the file holds, full by default
+      --strict                       PHYLIP taxon names take exactly 10 characters
+  -o, --output <FILE>                Write the order or the matrice to FILE, in the format of its extension.
+                                     solve writes one point per line, or an int64 array to a .npy FILE
+  -t, --tolerance <EPSILON>          Consider distances less than EPSILON apart as equal
+      --in-order                     check: only check the order of the file, without reordering
+      --one-based                    solve: number the points from 1
+  -q, --quiet                        check: print nothing, only set the exit code
+  -h, --help                         Print this help
+
+Exit codes: 0 if the matrice is Robinson, 1 if it isn't, 2 if the input can't be read.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Check,
+    Solve,
+    Reorder,
+    Help,
+}
+
+/// Format of a matrice file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    Phylip,
+    Npy,
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Format> {
+        return match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "tsv" | "tab" => Some(Format::Tsv),
+            "phylip" | "phy" | "dist" => Some(Format::Phylip),
+            "npy" => Some(Format::Npy),
+            _ => None,
+        };
+    }
+
+    fn of_path(path: &Path) -> Option<Format> {
+        return Format::parse(path.extension()?.to_str()?);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    /// Matrice file, the standard input if `None`
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub header: bool,
+    pub row_labels: bool,
+    pub triangle: Triangle,
+    pub strict: bool,
+    pub tolerance: Option<f64>,
+    pub in_order: bool,
+    pub one_based: bool,
+    pub quiet: bool,
+}
+
+impl Args {
+    ///Return the format of the input file
+    pub fn input_format(&self) -> Format {
+        return self
+            .format
+            .or_else(|| self.input.as_deref().and_then(Format::of_path))
+            .unwrap_or(Format::Csv);
+    }
+
+    ///Return the format of the output file given by its extension, `None` if it has no known one or there is no output file
+    pub fn output_file_format(&self) -> Option<Format> {
+        return self.output.as_deref().and_then(Format::of_path);
+    }
+
+    ///Return the format to write in, the one of the output file if it has a known extension, of the input file otherwise
+    pub fn output_format(&self) -> Format {
+        return self
+            .output_file_format()
+            .unwrap_or_else(|| self.input_format());
+    }
+
+    pub fn csv_format(&self, format: Format) -> CsvFormat {
+        let csv = if format == Format::Tsv {
+            CsvFormat::tsv()
+        } else {
+            CsvFormat::csv()
+        };
+        return csv
+            .with_header(self.header)
+            .with_row_labels(self.row_labels)
+            .with_triangle(self.triangle);
+    }
+
+    pub fn phylip_format(&self) -> PhylipFormat {
+        let phylip = if self.strict {
+            PhylipFormat::strict()
+        } else {
+            PhylipFormat::relaxed()
+        };
+        return phylip.with_triangle(self.triangle);
+    }
+}
+
+///Parse the command line arguments, without the program name
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        Some("check") => Command::Check,
+        Some("solve") => Command::Solve,
+        Some("reorder") => Command::Reorder,
+        Some("-h") | Some("--help") | Some("help") => Command::Help,
+        Some(other) => return Err(format!("unknown command {other:?}")),
+        None => return Err("missing command".to_string()),
+    };
+    let mut parsed = Args {
+        command,
+        input: None,
+        output: None,
+        format: None,
+        header: false,
+        row_labels: false,
+        triangle: Triangle::Full,
+        strict: false,
+        tolerance: None,
+        in_order: false,
+        one_based: false,
+        quiet: false,
+    };
+
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        // `--option=value` is the same as `--option value`
+        let (option, mut inline_value) = match arg.split_once('=') {
+            Some((option, value)) if arg.starts_with("--") => (option.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| match inline_value.take() {
+            Some(value) => Ok(value.to_string()),
+            None => args.next().ok_or(format!("{name} needs a value")),
+        };
+        match option.as_str() {
+            "-f" | "--format" => {
+                let format = value("--format")?;
+                parsed.format =
+                    Some(Format::parse(&format).ok_or(format!("unknown format {format:?}"))?);
+            }
+            "--header" => parsed.header = true,
+            "--row-labels" => parsed.row_labels = true,
+            "--triangle" => {
+                parsed.triangle = match value("--triangle")?.as_str() {
+                    "full" => Triangle::Full,
+                    "upper" => Triangle::Upper,
+                    "lower" => Triangle::Lower,
+                    other => return Err(format!("unknown triangle {other:?}")),
+                }
+            }
+            "--strict" => parsed.strict = true,
+            "-o" | "--output" => parsed.output = Some(PathBuf::from(value("--output")?)),
+            "-t" | "--tolerance" => {
+                let tolerance = value("--tolerance")?;
+                parsed.tolerance = Some(
+                    tolerance
+                        .parse()
+                        .map_err(|_| format!("invalid tolerance {tolerance:?}"))?,
+                );
+            }
+            "--in-order" => parsed.in_order = true,
+            "--one-based" => parsed.one_based = true,
+            "-q" | "--quiet" => parsed.quiet = true,
+            "-h" | "--help" => parsed.command = Command::Help,
+            "-" => positional.push(arg.clone()),
+            _ if option.starts_with('-') => return Err(format!("unknown option {option:?}")),
+            _ => positional.push(arg.clone()),
+        }
+        if inline_value.is_some() {
+            return Err(format!("{option} doesn't take a value"));
+        }
+    }
+
+    if positional.len() > 1 {
+        return Err("too many arguments, only one file can be read".to_string());
+    } else {
+        parsed.input = positional
+            .pop()
+            .filter(|file| file != "-")
+            .map(PathBuf::from);
+    }
+    return Ok(parsed);
+}
+
+#[test]
+fn test_parse() {
+    let args = |line: &str| parse(line.split_whitespace().map(str::to_string));
+
+    let solve = args("solve --header -t 0.5 --triangle=lower dist.tsv").unwrap();
+    assert_eq!(solve.command, Command::Solve);
+    assert_eq!(solve.input, Some(PathBuf::from("dist.tsv")));
+    assert_eq!(solve.tolerance, Some(0.5));
+    assert_eq!(solve.triangle, Triangle::Lower);
+    assert_eq!(solve.input_format(), Format::Tsv);
+    assert_eq!(
+        solve.csv_format(Format::Tsv),
+        CsvFormat::tsv()
+            .with_header(true)
+            .with_triangle(Triangle::Lower)
+    );
+
+    let reorder = args("reorder -f phylip -o out.npy -").unwrap();
+    assert_eq!(reorder.input, None);
+    assert_eq!(reorder.input_format(), Format::Phylip);
+    assert_eq!(reorder.output_format(), Format::Npy);
+    assert_eq!(args("solve dist.npy").unwrap().output_file_format(), None);
+    assert_eq!(args("check").unwrap().input_format(), Format::Csv);
+
+    assert!(args("").is_err());
+    assert!(args("sort dist.csv").is_err());
+    assert!(args("check a.csv b.csv").is_err());
+    assert!(args("check --unknown").is_err());
+    assert!(args("check --header=yes").is_err());
+    assert!(args("solve --tolerance").is_err());
+}
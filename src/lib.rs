@@ -1,5 +1,23 @@
+#![allow(clippy::needless_return)]
+
+pub use self::robinson::consecutive_ones_order;
+pub use self::robinson::AbsDiff;
+pub use self::robinson::BrokenTie;
+pub use self::robinson::Dissimilarity;
 pub use self::robinson::DistanceMatrice;
+pub use self::robinson::OrdFloat;
 pub use self::robinson::Robin;
+pub use self::robinson::{CompatibleOrder, NotRobinson, Obstruction, Violation};
+pub use self::robinson::{CompatibleOrders, PqNode, PqTree};
+pub use self::robinson::{CsvError, CsvFormat, LabeledMatrice, Triangle};
+pub use self::robinson::{Greatest, Similarity};
+pub use self::robinson::{MatriceError, Mean, Symmetrize};
+pub use self::robinson::{MeasuredViolation, RobinsonViolations};
+pub use self::robinson::{MmoduleTree, MmoduleTreeIter};
+pub use self::robinson::{NpyDistance, NpyError, NpzWriter};
+pub use self::robinson::{Permutation, PermutationError, PermutedView};
+pub use self::robinson::{PhylipError, PhylipFormat, PhylipNames};
+pub use self::robinson::{RectangularMatrice, Seriation};
 pub mod robinson {
 
     //! Robinson modules
@@ -8,59 +26,160 @@ pub mod robinson {
     //!
     //! It can determine if a given square matrice admit a compatible order in O(n²). If it does admit at least one, this crate will provide you with a permutation corresponding to a compatible order.
 
+    mod circular;
+    mod consecutive_ones;
+    mod csv;
+    mod dissimilarity;
     mod func;
+    mod labeled;
+    mod mmodule_tree;
+    mod npy;
+    mod npz;
+    mod obstruction;
+    mod ord_float;
+    mod permutation;
+    mod phylip;
+    mod pq_tree;
+    mod rectangular;
+    #[cfg(feature = "serde")]
+    mod serialization;
+    mod similarity;
+    mod storage;
+    mod strong;
+    mod tolerance;
+    mod validation;
+    mod view;
+    mod violations;
+
+    use func::get_empty_distance_matrice;
+    use storage::{condensed_points, Storage};
+    use tolerance::{cmp_within, Tolerance};
+    use validation::check_square;
+    use violations::{measure_violations, violations_of};
 
-    use func::{get_empty_distance_matrice, max};
+    pub use consecutive_ones::consecutive_ones_order;
+    pub use csv::{CsvError, CsvFormat, Triangle};
+    pub use dissimilarity::Dissimilarity;
+    pub use labeled::LabeledMatrice;
+    pub use mmodule_tree::{MmoduleTree, MmoduleTreeIter};
+    pub use npy::{NpyDistance, NpyError};
+    pub use npz::NpzWriter;
+    pub use obstruction::Obstruction;
+    pub use ord_float::OrdFloat;
+    pub use permutation::{Permutation, PermutationError};
+    pub use phylip::{PhylipError, PhylipFormat, PhylipNames};
+    pub use pq_tree::{CompatibleOrders, PqNode, PqTree};
+    pub use rectangular::{RectangularMatrice, Seriation};
+    pub use similarity::{Greatest, Similarity};
+    pub use strong::BrokenTie;
+    pub use tolerance::AbsDiff;
+    pub use validation::{MatriceError, Mean, Symmetrize};
+    pub use view::PermutedView;
+    pub use violations::{MeasuredViolation, RobinsonViolations};
 
-    use avl::AvlTreeMap;
+    use std::cmp::Ordering;
+    use std::fmt;
 
     ///This structure will be able to determine if its given `DistanceMatrice` admit a compatible order (using .resolve_robin) and provide you with one (in solved_permut).
-    pub struct Robin {
-        pub dist: DistanceMatrice,
-        pub solved_permut: Option<Vec<u32>>,
+    ///
+    /// Distances can be of any ordered type, see `DistanceMatrice`. Points are numbered from 0, point `x` being line `x` of the matrice.
+    ///
+    /// Any other source of distances implementing `Dissimilarity` can be used instead of a `DistanceMatrice`, with `Robin::from`.
+    pub struct Robin<T = u32, D = DistanceMatrice<T>> {
+        pub dist: D,
+        pub solved_permut: Option<Permutation>,
+        tolerance: Option<Tolerance<T>>,
     }
-    impl Robin {
+    impl<T: Ord + Copy> Robin<T> {
         ///Create a Robin object
         ///
         /// Needs a square matrice as argument
         ///
         /// Panic :
         ///
-        /// This function will panic if provided with a non square matrice
-        pub fn new(distance_mat: Vec<Vec<u32>>) -> Robin {
+        /// This function will panic if provided with a non square matrice, use `try_new` to get an error instead
+        pub fn new(distance_mat: Vec<Vec<T>>) -> Robin<T> {
             let n = distance_mat.len();
             for line in &distance_mat {
                 if line.len() != n {
                     panic!("Given distance matrice isn't square");
                 }
             }
-            return Robin {
-                dist: DistanceMatrice { distance_mat },
-                solved_permut: None,
-            };
+            return Robin::from(DistanceMatrice {
+                storage: Storage::Full(distance_mat),
+            });
         }
 
-        fn refine(&self, q: u32, s_list: Vec<u32>) -> Vec<Vec<u32>> {
-            let mut tree: AvlTreeMap<u32, Vec<u32>> = AvlTreeMap::new();
-            for elem in s_list {
-                match tree.get(&self.dist.d(q, elem)) {
-                    Some(l) => {
-                        tree.insert(self.dist.d(q, elem), [vec![elem], l.clone()].concat());
-                    }
-                    None => {
-                        tree.insert(self.dist.d(q, elem), vec![elem]);
-                    }
-                }
+        ///Create a Robin object, returning an error if the matrice is empty, isn't square or is too large
+        pub fn try_new(distance_mat: Vec<Vec<T>>) -> Result<Robin<T>, MatriceError> {
+            return Ok(Robin::from(DistanceMatrice::try_from(distance_mat)?));
+        }
+    }
+
+    impl<T: Ord + Copy, D: Dissimilarity<Distance = T>> Robin<T, D> {
+        ///Consider distances less than `epsilon` apart as equal, both while looking for a compatible order and while checking it
+        ///
+        /// Useful for floating point distances computed from real data, a matrice which is Robinson up to rounding noise will be recognized.
+        pub fn with_tolerance(mut self, epsilon: T) -> Robin<T, D>
+        where
+            T: AbsDiff,
+        {
+            self.tolerance = Some(Tolerance::new(epsilon));
+            return self;
+        }
+
+        ///Return the tolerance set with `with_tolerance`, if any
+        pub fn tolerance(&self) -> Option<T> {
+            self.tolerance.map(|tolerance| tolerance.epsilon())
+        }
+
+        fn cmp_d(&self, a: T, b: T) -> Ordering {
+            return cmp_within(self.tolerance, a, b);
+        }
+
+        /// Fills `keyed` with the points of `s_list` sorted by increasing distance to `q`, along with this distance, and `class_ends` with the end of each class of points at the same distance from `q`. Points at the same distance are kept in reverse order of `s_list`.
+        ///
+        /// With a tolerance, a class starts at its closest point and holds the following ones within the tolerance of it, so every two points of a class are within the tolerance as `is_robinson_with_tolerance` compares them. When there is a single class, whether its distances are exactly equal or only within the tolerance, `keyed` is left in reverse order of `s_list`.
+        ///
+        /// Return the number of classes. Points are bucketed by the rank of their distance in the row of `q` rather than sorted, so this takes O(|S|) once the row is ranked.
+        fn refine(
+            &self,
+            q: u32,
+            s_list: impl DoubleEndedIterator<Item = u32>,
+            buckets: &mut Buckets<T>,
+            keyed: &mut Vec<(T, u32)>,
+            class_ends: &mut Vec<usize>,
+        ) -> usize {
+            keyed.clear();
+            class_ends.clear();
+            keyed.extend(s_list.rev().map(|x| (self.dist.d(q, x), x)));
+            let d_min = match keyed.iter().map(|&(d_qx, _)| d_qx).min() {
+                Some(d_min) => d_min,
+                None => return 0,
+            };
+            // Most refinements don't split anything, there is no need to move the points in that case
+            if keyed
+                .iter()
+                .all(|&(d_qx, _)| self.cmp_d(d_qx, d_min) == Ordering::Equal)
+            {
+                class_ends.push(keyed.len());
+                return 1;
             }
-            let mut refined = Vec::new();
-            for bi in tree.values() {
-                refined.push(bi.clone());
+
+            buckets.sort_by_rank(&self.dist, q, keyed);
+            let mut first = d_min;
+            for (k, &(d_qx, _)) in keyed.iter().enumerate() {
+                if self.cmp_d(d_qx, first) == Ordering::Greater {
+                    class_ends.push(k);
+                    first = d_qx;
+                }
             }
-            return refined;
+            class_ends.push(keyed.len());
+            return class_ends.len();
         }
 
         fn separate_if_separable(&self, p: u32, x_prime: Vec<u32>) -> Option<Vec<VecPoint>> {
-            if x_prime.len() == 0 {
+            if x_prime.is_empty() {
                 return Some(vec![]);
             }
 
@@ -70,7 +189,7 @@ pub mod robinson {
             let b_delta = self.dist.d(x_min, x_max);
             let l_delta = self.dist.d(p, x_min);
 
-            if b_delta <= l_delta {
+            if self.cmp_d(b_delta, l_delta) != Ordering::Greater {
                 return Some(vec![VecPoint {
                     point: x_min,
                     vec: x_prime,
@@ -80,9 +199,9 @@ pub mod robinson {
                 let y = x_prime[y_ind];
                 let z = x_prime[y_ind + 1];
 
-                if (self.dist.d(x_min, y) <= l_delta)
-                    && (self.dist.d(z, x_max) <= l_delta)
-                    && (self.dist.d(y, z) >= l_delta)
+                if (self.cmp_d(self.dist.d(x_min, y), l_delta) != Ordering::Greater)
+                    && (self.cmp_d(self.dist.d(z, x_max), l_delta) != Ordering::Greater)
+                    && (self.cmp_d(self.dist.d(y, z), l_delta) != Ordering::Less)
                 {
                     let u1_vec = x_prime[..y_ind + 1].to_vec();
                     let u2_vec = x_prime[y_ind + 1..].to_vec();
@@ -103,191 +222,747 @@ pub mod robinson {
 
         fn recursive_refine(
             &self,
+            buckets: &mut Buckets<T>,
             p: u32,
             in_list: Vec<u32>,
             s_list: Vec<u32>,
             out_list: Vec<u32>,
         ) -> Vec<Vec<u32>> {
-            if in_list.len() == 0 && out_list.len() == 0 {
-                return vec![s_list];
-            } else {
-                let q: u32;
+            // Every refined S is written once in the arena, the in/out lists of the calls on its parts are then just segments of it pushed on top of the parent lists.
+            // This keeps the whole refinement in O(n²), copying the lists for each call would cost O(n) per call.
+            let mut arena: Vec<u32> = [in_list.as_slice(), &out_list, &s_list].concat();
+            let mut pivots = PivotLists::new();
+            let in_head = pivots.push(
+                Segment {
+                    start: 0,
+                    len: in_list.len(),
+                    reversed: false,
+                },
+                NIL,
+            );
+            let out_head = pivots.push(
+                Segment {
+                    start: in_list.len(),
+                    len: out_list.len(),
+                    reversed: false,
+                },
+                NIL,
+            );
+            let s_segment = Segment {
+                start: in_list.len() + out_list.len(),
+                len: s_list.len(),
+                reversed: false,
+            };
 
-                match in_list.first() {
-                    Some(l) => q = *l,
-                    None => q = *out_list.first().unwrap(),
-                }
+            let mut keyed: Vec<(T, u32)> = Vec::new();
+            let mut class_ends: Vec<usize> = Vec::new();
+            let mut refined: Vec<Vec<u32>> = Vec::new();
+            // The recursion depth can reach the number of points, so the pending calls are kept on an explicit work stack instead of the thread stack
+            let mut stack: Vec<RefineFrame> = Vec::new();
+            let mut call = Some(RefineCall {
+                in_head,
+                s_segment,
+                out_head,
+            });
+
+            loop {
+                if let Some(RefineCall {
+                    in_head,
+                    s_segment,
+                    out_head,
+                }) = call.take()
+                {
+                    if in_head == NIL && out_head == NIL {
+                        refined.push(s_segment.points(&arena).collect());
+                    } else if s_segment.len <= 1 {
+                        // A single point can't be split any further
+                        if s_segment.len == 1 {
+                            refined.push(s_segment.points(&arena).collect());
+                        }
+                    } else {
+                        let is_q_out = in_head == NIL;
+                        let (q, in_new, out_new) = if is_q_out {
+                            let (q, out_new) = pivots.pop(&arena, out_head);
+                            (q, NIL, out_new)
+                        } else {
+                            let (q, in_new) = pivots.pop(&arena, in_head);
+                            (q, in_new, out_head)
+                        };
 
-                let s_list_list = self.refine(q, s_list);
+                        let classes = self.refine(
+                            q,
+                            s_segment.points(&arena),
+                            buckets,
+                            &mut keyed,
+                            &mut class_ends,
+                        );
+                        if classes == 1 {
+                            // S isn't split, only read backward
+                            call = Some(RefineCall {
+                                in_head: in_new,
+                                s_segment: s_segment.reversed(),
+                                out_head: out_new,
+                            });
+                            continue;
+                        }
 
-                let mut s_prime_list_list: Vec<Vec<u32>>;
-                if out_list.contains(&q) {
-                    let mut alpha = s_list_list.len();
-                    for j in 0..s_list_list.len() {
-                        if self.dist.d(*s_list_list[j].first().unwrap(), q) > self.dist.d(p, q) {
-                            alpha = j;
-                            break;
+                        let mut class_start = 0;
+                        let mut s_list_list: Vec<&[(T, u32)]> = Vec::with_capacity(classes);
+                        for &class_end in &class_ends {
+                            s_list_list.push(&keyed[class_start..class_end]);
+                            class_start = class_end;
+                        }
+                        if is_q_out {
+                            let d_pq = self.dist.d(p, q);
+                            let alpha = s_list_list
+                                .iter()
+                                .position(|s_j| self.cmp_d(s_j[0].0, d_pq) == Ordering::Greater)
+                                .unwrap_or(s_list_list.len());
+                            s_list_list[..alpha].reverse();
                         }
-                    }
 
-                    s_prime_list_list = Vec::new();
-                    for i in 1..alpha + 1 {
-                        s_prime_list_list.push(s_list_list[alpha - i].to_vec());
-                    }
-                    for i in alpha..s_list_list.len() {
-                        s_prime_list_list.push(s_list_list[i].to_vec());
+                        let base = arena.len();
+                        let mut class_ends = Vec::with_capacity(s_list_list.len());
+                        for s_j in s_list_list {
+                            arena.extend(s_j.iter().map(|&(_, x)| x));
+                            class_ends.push(arena.len() - base);
+                        }
+                        stack.push(RefineFrame {
+                            base,
+                            pivots_len: pivots.len(),
+                            class_ends,
+                            in_list_new: in_new,
+                            out_list_new: out_new,
+                            next: 0,
+                        });
                     }
-                } else {
-                    s_prime_list_list = s_list_list;
                 }
 
-                let mut ti_list: Vec<Vec<Vec<u32>>> = Vec::new();
-
-                let mut in_list_new = in_list.clone();
-                in_list_new.retain(|&x| x != q);
-
-                let mut out_list_new = out_list.clone();
-                out_list_new.retain(|&x| x != q);
-                for i in 0..s_prime_list_list.len() {
-                    let ini = [s_prime_list_list[0..i].concat(), in_list_new.clone()].concat();
-                    let outi = [s_prime_list_list[i + 1..].concat(), out_list_new.clone()].concat();
-                    ti_list.push(self.recursive_refine(p, ini, s_prime_list_list[i].clone(), outi));
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return refined,
+                };
+                let i = frame.next;
+                if i == frame.class_ends.len() {
+                    arena.truncate(frame.base);
+                    pivots.truncate(frame.pivots_len);
+                    stack.pop();
+                    continue;
                 }
+                frame.next += 1;
 
-                return ti_list.concat();
+                let class_start = if i == 0 { 0 } else { frame.class_ends[i - 1] };
+                let class_end = frame.class_ends[i];
+                let s_prime_len = *frame.class_ends.last().unwrap();
+                // The classes before S'_i go in front of the in list, the ones after in front of the out list
+                let in_i = pivots.push(
+                    Segment {
+                        start: frame.base,
+                        len: class_start,
+                        reversed: false,
+                    },
+                    frame.in_list_new,
+                );
+                let out_i = pivots.push(
+                    Segment {
+                        start: frame.base + class_end,
+                        len: s_prime_len - class_end,
+                        reversed: false,
+                    },
+                    frame.out_list_new,
+                );
+                call = Some(RefineCall {
+                    in_head: in_i,
+                    s_segment: Segment {
+                        start: frame.base + class_start,
+                        len: class_end - class_start,
+                        reversed: false,
+                    },
+                    out_head: out_i,
+                });
             }
         }
 
-        fn sort_by_bipartition(&self, p: u32, x_list: Vec<u32>) -> Vec<u32> {
+        fn sort_by_bipartition(&self, p: u32, x_list: &[u32], sides: &mut [Side]) -> Vec<u32> {
             //avec x_list qui est en fait X/{p}
-            let mut l_list = Vec::new();
-            let mut r_list = Vec::new();
-            let mut undecided = x_list.clone();
-            undecided.reverse();
-
-            for q in &undecided.clone() {
-                if (&undecided).contains(q) {
-                    r_list.insert(0, *q);
-                    undecided.retain(|&x| x != *q);
+            // Both lists are built from their end, pushing is inserting in front
+            let mut l_list_rev: Vec<u32> = Vec::new();
+            let mut r_list_rev: Vec<u32> = Vec::new();
+            let mut undecided: Vec<u32> = x_list.iter().rev().copied().collect();
+            let pivots = undecided.clone();
+
+            for q in pivots {
+                if sides[q as usize] == Side::Undecided {
+                    r_list_rev.push(q);
+                    sides[q as usize] = Side::Right;
                 }
-                let mut skipped = Vec::new();
-                let does_l_contain_q = l_list.contains(q);
-                let does_r_contain_q = r_list.contains(q);
+                let q_side = sides[q as usize];
+                let d_pq = self.dist.d(p, q);
+                let mut skipped: Vec<u32> = Vec::new();
 
+                // q may still be in undecided, its side tells it was just decided
                 for x in undecided {
-                    if self.dist.d(x, *q) == self.dist.d(p, *q) {
-                        skipped.insert(0, x);
+                    if sides[x as usize] != Side::Undecided {
+                        continue;
+                    }
+                    let d_xq = self.dist.d(x, q);
+                    let cmp_xq_pq = self.cmp_d(d_xq, d_pq);
+                    if cmp_xq_pq == Ordering::Equal {
+                        skipped.push(x);
+                    } else if (cmp_xq_pq == Ordering::Less && q_side == Side::Left)
+                        || (cmp_xq_pq == Ordering::Greater && q_side == Side::Right)
+                    {
+                        l_list_rev.push(x);
+                        sides[x as usize] = Side::Left;
+                        for y in skipped.drain(..) {
+                            r_list_rev.push(y);
+                            sides[y as usize] = Side::Right;
+                        }
                     } else {
-                        if (self.dist.d(x, *q) < self.dist.d(p, *q) && does_l_contain_q)
-                            || (self.dist.d(x, *q) > self.dist.d(p, *q) && does_r_contain_q)
-                        {
-                            l_list.insert(0, x);
-                            r_list = [skipped.clone(), r_list].concat();
-                        } else {
-                            r_list.insert(0, x);
-                            l_list = [skipped.clone(), l_list].concat();
+                        r_list_rev.push(x);
+                        sides[x as usize] = Side::Right;
+                        for y in skipped.drain(..) {
+                            l_list_rev.push(y);
+                            sides[y as usize] = Side::Left;
                         }
-                        skipped = Vec::new();
                     }
                 }
-                skipped.reverse();
                 undecided = skipped;
             }
-            l_list.reverse();
-            return [l_list, [p].to_vec(), r_list].concat();
+
+            for &x in x_list {
+                sides[x as usize] = Side::Undecided;
+            }
+            r_list_rev.reverse();
+            return [l_list_rev, vec![p], r_list_rev].concat();
         }
 
         fn find_compatible_order(&self, x_list: Vec<u32>) -> Vec<u32> {
-            if x_list.len() == 0 {
-                return vec![];
-            } else {
-                let p = *x_list.first().unwrap();
-                let x_prime_list = x_list[1..].to_vec();
-                let mut c_list = self.recursive_refine(p, vec![p], x_prime_list, vec![]);
-                let mut represented_copoints: Vec<VecPoint> = Vec::new();
-
-                for i in (0..c_list.len()).rev() {
-                    let c_prime_i =
-                        self.find_compatible_order(std::mem::replace(&mut c_list[i], vec![]));
-                    represented_copoints = [
-                        match self.separate_if_separable(p, c_prime_i) {
-                            Some(l) => l,
-                            None => vec![],
-                        },
-                        represented_copoints,
-                    ]
-                    .concat()
+            return self.decompose(x_list, false).0;
+        }
+
+        /// Return the order found for `x_list`, along with the tree of the copoints it went through if `build_tree`
+        fn decompose(&self, x_list: Vec<u32>, build_tree: bool) -> (Vec<u32>, Option<MmoduleTree>) {
+            if x_list.is_empty() {
+                return (vec![], None);
+            }
+            // Indexed by point, shared by every call of sort_by_bipartition and assemble_copoints
+            let mut sides = vec![Side::Undecided; self.dist.len()];
+            let mut block_of = vec![0; self.dist.len()];
+            let mut buckets = Buckets::new(self.dist.len());
+            // Copoints are ordered depth first. As for recursive_refine, the nesting of copoints can be as deep as the number of points so it's kept on an explicit work stack
+            let mut stack: Vec<OrderFrame> = vec![self.order_frame(&mut buckets, x_list)];
+
+            loop {
+                let frame = stack.last_mut().unwrap();
+                match frame.c_list.pop() {
+                    Some(c_i) => {
+                        let child = self.order_frame(&mut buckets, c_i);
+                        stack.push(child);
+                    }
+                    None => {
+                        let mut frame = stack.pop().unwrap();
+                        // c_list was emptied from its end
+                        frame.c_prime_list.reverse();
+                        frame.copoint_trees.reverse();
+                        let compatible_order = self.assemble_copoints(
+                            frame.p,
+                            frame.c_prime_list,
+                            &mut sides,
+                            &mut block_of,
+                        );
+                        let tree = if build_tree {
+                            Some(MmoduleTree {
+                                pivot: frame.p,
+                                points: compatible_order.clone(),
+                                copoints: frame.copoint_trees,
+                            })
+                        } else {
+                            None
+                        };
+                        match stack.last_mut() {
+                            Some(parent) => {
+                                parent.c_prime_list.push(compatible_order);
+                                parent.copoint_trees.extend(tree);
+                            }
+                            None => return (compatible_order, tree),
+                        }
+                    }
+                }
+            }
+        }
+
+        fn order_frame(&self, buckets: &mut Buckets<T>, x_list: Vec<u32>) -> OrderFrame {
+            let p = *x_list.first().unwrap();
+            let x_prime_list = x_list[1..].to_vec();
+            let c_list = self.recursive_refine(buckets, p, vec![p], x_prime_list, vec![]);
+            return OrderFrame {
+                p,
+                c_list,
+                c_prime_list: Vec::new(),
+                copoint_trees: Vec::new(),
+            };
+        }
+
+        fn assemble_copoints(
+            &self,
+            p: u32,
+            c_prime_list: Vec<Vec<u32>>,
+            sides: &mut [Side],
+            block_of: &mut [usize],
+        ) -> Vec<u32> {
+            let mut represented_copoints: Vec<VecPoint> = Vec::new();
+
+            for c_prime_i in c_prime_list {
+                match self.separate_if_separable(p, c_prime_i.clone()) {
+                    Some(l) => represented_copoints.extend(l),
+                    // Not separable : the space isn't Robinson. The copoint is kept as a single block so the permutation stays complete
+                    None => represented_copoints.push(VecPoint {
+                        point: c_prime_i[0],
+                        vec: c_prime_i,
+                    }),
                 }
+            }
+
+            let mut points_list: Vec<u32> = Vec::new();
+            let mut vec_list: Vec<Vec<u32>> = Vec::new();
+
+            for copoint in represented_copoints {
+                block_of[copoint.point as usize] = vec_list.len();
+                points_list.push(copoint.point);
+                vec_list.push(copoint.vec);
+            }
+
+            let points_sorted_list = self.sort_by_bipartition(p, &points_list, sides);
 
-                let mut points_list: Vec<u32> = Vec::new();
-                let mut vec_list: Vec<Vec<u32>> = Vec::new();
+            let mut compatible_order: Vec<u32> = Vec::new();
 
-                for copoint in represented_copoints {
-                    points_list.push(copoint.point);
-                    vec_list.push(copoint.vec);
+            for point_sorted in points_sorted_list {
+                if point_sorted == p {
+                    compatible_order.push(p);
+                } else {
+                    compatible_order.append(&mut vec_list[block_of[point_sorted as usize]]);
                 }
+            }
+            return compatible_order;
+        }
+    }
 
-                let points_sorted_list = self.sort_by_bipartition(p, points_list.clone());
+    impl<T: Ord + Copy + Default, D: Dissimilarity<Distance = T>> Robin<T, D> {
+        /// Tries to find a compatible order for the distance matrice.
+        ///
+        /// Return `Ok` with the compatible order if the matrice admit one (up to the tolerance, if one was set with `with_tolerance`). Otherwise return `Err` with the candidate permutation the algorithm built and the first violation `is_robinson` found in it.
+        pub fn solve(&self) -> Result<CompatibleOrder, NotRobinson> {
+            let x_list = (0..self.dist.len() as u32).collect();
+            let permut_found = Permutation::from_vec_unchecked(self.find_compatible_order(x_list));
 
-                let mut compatible_order: Vec<Vec<u32>> = Vec::new();
+            match PermutedView::new_unchecked(&self.dist, &permut_found)
+                .find_violation_within(self.tolerance)
+            {
+                None => {
+                    return Ok(CompatibleOrder {
+                        permut: permut_found,
+                    })
+                }
+                Some(violation) => {
+                    // Translate the positions of the permuted matrice back to the points of self.dist
+                    let violation = Violation {
+                        i: permut_found[violation.i as usize],
+                        neighbour: permut_found[violation.neighbour as usize],
+                        j: permut_found[violation.j as usize],
+                    };
+                    return Err(NotRobinson {
+                        permut: permut_found,
+                        violation,
+                    });
+                }
+            }
+        }
 
-                for point_sorted in points_sorted_list {
-                    if point_sorted == p {
-                        compatible_order.push(vec![p]);
-                        continue;
+        /// Tries to find a compatible order for the distance matrice.
+        ///
+        /// Return true if the matrice admit a compatible order, false otherwise.
+        ///
+        /// Stores the permutation (a compatible order if the distance matrice admit at least one) found in self.solved_permut. Be careful, it'll always store a permutation whether or not it admits a compatible order. Prefer `solve` which can't be misused this way.
+        pub fn resolve_robin(&mut self) -> bool {
+            match self.solve() {
+                Ok(order) => {
+                    self.solved_permut = Some(order.permut);
+                    return true;
+                }
+                Err(not_robinson) => {
+                    self.solved_permut = Some(not_robinson.permut);
+                    return false;
+                }
+            }
+        }
+
+        /// Return a certificate that the distance matrice isn't Robinson, `None` if it admits a compatible order.
+        ///
+        /// Points are removed, by chunks then one by one, as long as the remaining ones still admit no compatible order. Each try runs the algorithm on the remaining points, so it can take up to O(n³).
+        pub fn obstruction(&self) -> Option<Obstruction> {
+            let mut points: Vec<u32> = (0..self.dist.len() as u32).collect();
+            if self.admits_compatible_order(&points) {
+                return None;
+            }
+
+            // Removing points from a Robinson space leaves it Robinson, so a point which can't be removed now can't be removed later either
+            let mut chunk = (points.len() / 2).max(1);
+            loop {
+                let mut start = 0;
+                while start < points.len() {
+                    let end = (start + chunk).min(points.len());
+                    let remaining = [&points[..start], &points[end..]].concat();
+                    if self.admits_compatible_order(&remaining) {
+                        start = end;
+                    } else {
+                        points = remaining;
                     }
-                    for point_ind in 0..points_list.len() {
-                        if points_list[point_ind] == point_sorted {
-                            compatible_order
-                                .push(std::mem::replace(&mut vec_list[point_ind], vec![]))
+                }
+                if chunk == 1 {
+                    break;
+                }
+                chunk /= 2;
+            }
+
+            let mut forbidden = Vec::new();
+            for (k, &i) in points.iter().enumerate() {
+                for &j in &points[k + 1..] {
+                    let d_ij = self.dist.d(i, j);
+                    for &neighbour in &points {
+                        let d_max = self.dist.d(i, neighbour).max(self.dist.d(neighbour, j));
+                        if neighbour != i
+                            && neighbour != j
+                            && self.cmp_d(d_ij, d_max) == Ordering::Less
+                        {
+                            forbidden.push(Violation { i, neighbour, j });
                         }
                     }
                 }
-                return compatible_order.concat();
             }
+            return Some(Obstruction { points, forbidden });
         }
 
-        /// Tries to find a compatible order for the distance matrice.
+        fn admits_compatible_order(&self, points: &[u32]) -> bool {
+            let distance_mat = points
+                .iter()
+                .map(|&x| points.iter().map(|&y| self.dist.d(x, y)).collect())
+                .collect();
+            let restricted = Robin {
+                dist: DistanceMatrice {
+                    storage: Storage::Full(distance_mat),
+                },
+                solved_permut: None,
+                tolerance: self.tolerance,
+            };
+            return restricted.solve().is_ok();
+        }
+
+        /// Return a PQ-tree representing every compatible order of the distance matrice, `None` if it doesn't admit any.
         ///
-        /// Return true if the matrice admit a compatible order, false otherwise.
+        /// An order is compatible if and only if every ball `{y : d(x, y) <= r}` is an interval of it, the tree is built from the balls on the order found by the algorithm.
         ///
-        /// Stores the permutation (a compatible order if the distance matrice admit at least one) found in self.solved_permut. Be careful, it'll always store a permutation whether or not it admits a compatible order.
+        /// Panic :
         ///
-        /// If you want to resolve matrice bigger then a 1000x1000, you might need to increase your thread stack size. This is due to the depth of recursives function of this algorithm.
-        pub fn resolve_robin(&mut self) -> bool {
-            let mut x_list = Vec::new();
-            let n = self.dist.distance_mat.len();
-            for i in 1..n + 1 {
-                x_list.push(i as u32);
+        /// This function will panic if a tolerance was set with `with_tolerance`. An order which is Robinson only up to the tolerance doesn't make every ball an interval, so the orders compatible up to a tolerance can't be represented this way.
+        pub fn pq_tree(&self) -> Option<PqTree> {
+            assert!(
+                self.tolerance.is_none(),
+                "compatible orders up to a tolerance can't be represented by a PQ-tree"
+            );
+            let n = self.dist.len();
+            let permut_found = Permutation::from_vec_unchecked(
+                self.find_compatible_order((0..n as u32).collect()),
+            );
+            let result_mat = PermutedView::new_unchecked(&self.dist, &permut_found);
+            if result_mat.find_violation().is_some() {
+                return None;
+            }
+
+            // On a compatible order, distances only increase moving away from x, balls centered on x grow one side or the other
+            let mut balls: Vec<(usize, usize)> = Vec::new();
+            for x in 0..n {
+                let d_x = |y: usize| result_mat.d(x as u32, y as u32);
+                let (mut first, mut last) = (x, x);
+                loop {
+                    let radius = match (first > 0, last + 1 < n) {
+                        (true, true) => d_x(first - 1).min(d_x(last + 1)),
+                        (true, false) => d_x(first - 1),
+                        (false, true) => d_x(last + 1),
+                        (false, false) => break,
+                    };
+                    while first > 0 && d_x(first - 1) <= radius {
+                        first -= 1;
+                    }
+                    while last + 1 < n && d_x(last + 1) <= radius {
+                        last += 1;
+                    }
+                    balls.push((first, last));
+                }
             }
-            let permut_found = self.find_compatible_order(x_list);
+            return Some(PqTree::from_intervals(permut_found.as_slice(), balls));
+        }
+
+        /// Iterate over every compatible order of the distance matrice, nothing if it doesn't admit any.
+        ///
+        /// Orders are generated lazily from `pq_tree`, there can be up to n! of them. As `pq_tree`, this function will panic if a tolerance was set.
+        pub fn compatible_orders(&self) -> CompatibleOrders {
+            return match self.pq_tree() {
+                Some(tree) => tree.orders(),
+                None => CompatibleOrders::empty(),
+            };
+        }
+
+        /// Return the number of compatible orders of the distance matrice (an order and its reverse are both counted), without enumerating them. Saturates at `u128::MAX`.
+        ///
+        /// As `pq_tree`, this function will panic if a tolerance was set.
+        pub fn count_compatible_orders(&self) -> u128 {
+            return match self.pq_tree() {
+                Some(tree) => tree.count_orders(),
+                None => 0,
+            };
+        }
+    }
+
+    impl<T: Ord + Copy, D: Dissimilarity<Distance = T>> Robin<T, D> {
+        /// Return the decomposition of the points into copoints the algorithm went through, `None` for an empty matrice.
+        ///
+        /// The root is the first point and its copoints, each copoint is then decomposed the same way around its own first point. The `points` of each node are in the order found for them, so the `points` of the root are the permutation `solve` returns.
+        ///
+        /// If the matrice isn't Robinson, the tree is still built but its orders aren't compatible ones.
+        pub fn mmodule_tree(&self) -> Option<MmoduleTree> {
+            let x_list = (0..self.dist.len() as u32).collect();
+            return self.decompose(x_list, true).1;
+        }
+    }
 
-            let result_mat = self.dist.permut_matrice(&permut_found);
+    /// Use a `DistanceMatrice` built beforehand, for instance by `DistanceMatrice::new_checked`
+    impl<T, D: Dissimilarity<Distance = T>> From<D> for Robin<T, D> {
+        fn from(dist: D) -> Robin<T, D> {
+            return Robin {
+                dist,
+                solved_permut: None,
+                tolerance: None,
+            };
+        }
+    }
+
+    /// A compatible order of a `DistanceMatrice`, as found by `Robin::solve`.
+    ///
+    /// Applying `permut` to the matrice (with `permut_matrice`) gives a matrice respecting the Robinson property.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct CompatibleOrder {
+        pub permut: Permutation,
+    }
 
-            self.solved_permut = Some(permut_found);
+    /// Returned by `Robin::solve` when the `DistanceMatrice` doesn't admit any compatible order.
+    ///
+    /// `permut` is the candidate permutation built by the algorithm, `violation` is the first Robinson inequality it breaks. Points of `violation` are the points of the original matrice, not positions in `permut`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    pub struct NotRobinson {
+        pub permut: Permutation,
+        pub violation: Violation,
+    }
 
-            return result_mat.is_robinson();
+    impl fmt::Display for NotRobinson {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let Violation { i, neighbour, j } = self.violation;
+            write!(
+                f,
+                "distance matrice isn't Robinson : {neighbour} lies between {i} and {j} but d({i}, {j}) < max(d({i}, {neighbour}), d({neighbour}, {j}))"
+            )
         }
     }
-    ///This structure is just a `Vec<Vec<u32>>`.
+
+    impl std::error::Error for NotRobinson {}
+
+    /// A triple breaking the Robinson property.
+    ///
+    /// `neighbour` lies between `i` and `j` (it is either right after `i` or right before `j`) but `d(i, j) < max(d(i, neighbour), d(neighbour, j))`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Violation {
+        pub i: u32,
+        pub neighbour: u32,
+        pub j: u32,
+    }
+    ///This structure is a square matrice of distances, of which only the upper triangle is read.
     ///
-    /// In the case of this crate, it'll always be a square matrice.
-    pub struct DistanceMatrice {
-        pub distance_mat: Vec<Vec<u32>>,
+    /// It is either stored as a `Vec<Vec<T>>` (see `TryFrom<Vec<Vec<T>>>`) or as a condensed vector holding only the upper triangle (see `from_condensed`), which takes half the memory in a single allocation.
+    ///
+    /// Distances can be of any type with a total order, `u32` by default. Floating point distances can be wrapped in `OrdFloat`.
+    pub struct DistanceMatrice<T = u32> {
+        storage: Storage<T>,
+    }
+
+    impl<T: Copy> DistanceMatrice<T> {
+        ///Return the number of points
+        pub fn len(&self) -> usize {
+            return self.storage.len();
+        }
+
+        pub fn is_empty(&self) -> bool {
+            return self.len() == 0;
+        }
+
+        ///Return true if the distances are stored as a condensed vector
+        pub fn is_condensed(&self) -> bool {
+            return matches!(self.storage, Storage::Condensed { .. });
+        }
+
+        ///Return the matrice as a `Vec<Vec<T>>`
+        ///
+        /// The lower triangle of a condensed matrice is filled with zeros (`T::default()`).
+        pub fn to_rows(&self) -> Vec<Vec<T>> {
+            return (0..self.len()).map(|i| self.storage.line(i)).collect();
+        }
+
+        ///Return the upper triangle without the diagonal, row after row, as SciPy's condensed distance vectors
+        pub fn to_condensed(&self) -> Vec<T> {
+            let n = self.len();
+            return (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| self.storage.upper(i, j)))
+                .collect();
+        }
     }
 
-    impl DistanceMatrice {
-        fn d(&self, q: u32, x: u32) -> u32 {
+    impl<T: Ord + Copy> Dissimilarity for DistanceMatrice<T> {
+        type Distance = T;
+
+        fn len(&self) -> usize {
+            return self.storage.len();
+        }
+
+        fn d(&self, q: u32, x: u32) -> T {
             if q <= x {
-                return self.distance_mat[(q - 1) as usize][(x - 1) as usize];
+                return self.storage.upper(q as usize, x as usize);
             } else {
-                return self.distance_mat[(x - 1) as usize][(q - 1) as usize];
+                return self.storage.upper(x as usize, q as usize);
             }
         }
+    }
+
+    impl<T: Ord + Copy> DistanceMatrice<T> {
+        ///Return true if the given `DistanceMatrice` respect the Robinson property, false otherwise
+        pub fn is_robinson(&self) -> bool {
+            return self.find_violation().is_none();
+        }
+
+        ///Return true if the matrice holds similarities decreasing away from the diagonal, false otherwise. The diagonal isn't read, see `Similarity`.
+        pub fn is_robinson_similarity(&self) -> bool
+        where
+            T: Greatest,
+        {
+            return Similarity(self).is_robinson();
+        }
+
+        ///Return the first triple of the `DistanceMatrice` breaking the Robinson property, `None` if it respects it
+        pub fn find_violation(&self) -> Option<Violation> {
+            return self.find_violation_within(None);
+        }
+
+        ///Return true if the given `DistanceMatrice` respect the Robinson property up to `epsilon`, false otherwise
+        ///
+        /// Inequalities `d(i, j) >= d(i + 1, j)` and `d(i, j) >= d(i, j - 1)` are only broken if the difference is greater than `epsilon`.
+        pub fn is_robinson_with_tolerance(&self, epsilon: T) -> bool
+        where
+            T: AbsDiff,
+        {
+            return self.find_violation_with_tolerance(epsilon).is_none();
+        }
+
+        ///Return the first triple of the `DistanceMatrice` breaking the Robinson property by more than `epsilon`, `None` if it respects it up to `epsilon`
+        pub fn find_violation_with_tolerance(&self, epsilon: T) -> Option<Violation>
+        where
+            T: AbsDiff,
+        {
+            return self.find_violation_within(Some(Tolerance::new(epsilon)));
+        }
+
+        fn find_violation_within(&self, tolerance: Option<Tolerance<T>>) -> Option<Violation> {
+            return self
+                .violations_within(tolerance)
+                .next()
+                .map(|(violation, _, _)| violation);
+        }
+
+        /// Iterate over the broken inequalities `d(i, j) >= d(i + 1, j)` then `d(i, j) >= d(i, j - 1)`, row by row, along with `d(i, j)` and the distance greater than it
+        fn violations_within(
+            &self,
+            tolerance: Option<Tolerance<T>>,
+        ) -> impl Iterator<Item = (Violation, T, T)> + '_ {
+            return violations_of(self.len() as u32, move |q, x| self.d(q, x), tolerance);
+        }
+
+        ///Return a view of the matrice with the given permutation applied, reading distances from this matrice instead of building the permuted one
+        ///
+        /// Panic :
+        ///
+        /// This function will panic if `permut` doesn't have as many points as the matrice, use `try_permuted` to get an error instead
+        pub fn permuted<'a>(&'a self, permut: &'a Permutation) -> PermutedView<'a, Self> {
+            assert_eq!(
+                permut.len(),
+                self.len(),
+                "permutation and distance matrice sizes differ"
+            );
+            return PermutedView::new_unchecked(self, permut);
+        }
+
+        ///Return a view of the matrice with the given permutation applied, or an error if `permut` doesn't have as many points as the matrice
+        pub fn try_permuted<'a>(
+            &'a self,
+            permut: &'a Permutation,
+        ) -> Result<PermutedView<'a, Self>, MatriceError> {
+            return PermutedView::new(self, permut);
+        }
+    }
+
+    impl<T: Ord + AbsDiff> DistanceMatrice<T> {
+        ///Return every broken inequality of the Robinson property, with how much it is broken by
+        ///
+        /// The inequalities are `d(i, j) >= d(i + 1, j)` and `d(i, j) >= d(i, j - 1)` for `i < j`, the first one `find_violation` would return comes first.
+        pub fn robinson_violations(&self) -> RobinsonViolations<T> {
+            return self.collect_violations(None);
+        }
+
+        ///Return every inequality of the Robinson property broken by more than `epsilon`, see `robinson_violations`
+        pub fn robinson_violations_with_tolerance(&self, epsilon: T) -> RobinsonViolations<T> {
+            return self.collect_violations(Some(Tolerance::new(epsilon)));
+        }
+
+        fn collect_violations(&self, tolerance: Option<Tolerance<T>>) -> RobinsonViolations<T> {
+            return measure_violations(self.len(), self.violations_within(tolerance));
+        }
+    }
 
+    impl<T: Ord + Copy + Default> DistanceMatrice<T> {
         ///Return a new `DistanceMatrice` with the given permuation applied
-        pub fn permut_matrice(&self, permut: &Vec<u32>) -> DistanceMatrice {
-            let mut new_matrice = get_empty_distance_matrice(self.distance_mat.len() as u32);
+        ///
+        /// The new matrice is stored the same way as this one. The lower triangle of a `Vec<Vec<T>>` is filled with `T::default()`
+        ///
+        /// Panic :
+        ///
+        /// This function will panic if `permut` doesn't have as many points as the matrice, use `try_permut_matrice` to get an error instead
+        pub fn permut_matrice(&self, permut: &Permutation) -> DistanceMatrice<T> {
+            assert_eq!(
+                permut.len(),
+                self.len(),
+                "permutation and distance matrice sizes differ"
+            );
+            let n = self.len();
+
+            if let Storage::Condensed { zero, .. } = self.storage {
+                let values = (0..n)
+                    .flat_map(|i| (i + 1..n).map(move |j| self.d(permut[i], permut[j])))
+                    .collect();
+                return DistanceMatrice {
+                    storage: Storage::Condensed { n, values, zero },
+                };
+            }
+
+            let mut new_matrice = get_empty_distance_matrice(n as u32);
 
             for i in 0..new_matrice.len() {
                 for j in i..new_matrice.len() {
@@ -296,34 +971,154 @@ pub mod robinson {
             }
 
             return DistanceMatrice {
-                distance_mat: new_matrice,
+                storage: Storage::Full(new_matrice),
             };
         }
 
-        ///Return true if the given `DistanceMatrice` respect the Robinson property, false otherwise
-        pub fn is_robinson(&self) -> bool {
-            for i in 1..self.distance_mat.len() {
-                for j in i + 1..self.distance_mat.len() + 1 {
-                    if self.d(i as u32, j as u32)
-                        < max(
-                            self.d((i + 1) as u32, j as u32),
-                            self.d(i as u32, (j - 1) as u32),
-                        )
-                    {
-                        return false;
-                    }
+        ///Return a new `DistanceMatrice` with the given permuation applied, or an error if `permut` doesn't have as many points as the matrice
+        pub fn try_permut_matrice(
+            &self,
+            permut: &Permutation,
+        ) -> Result<DistanceMatrice<T>, MatriceError> {
+            if permut.len() != self.len() {
+                return Err(MatriceError::PermutationLength {
+                    len: permut.len(),
+                    expected: self.len(),
+                });
+            }
+            return Ok(self.permut_matrice(permut));
+        }
+
+        ///Create a `DistanceMatrice` from the upper triangle of a matrice without its diagonal, row after row (the layout of SciPy's condensed distance vectors)
+        ///
+        /// `values` holds `d(0, 1), d(0, 2), ..., d(0, n - 1), d(1, 2), ..., d(n - 2, n - 1)`, so its length must be `n(n - 1) / 2`. The diagonal is zero (`T::default()`).
+        ///
+        /// An empty vector is rejected with `MatriceError::Empty`, as `try_new` rejects an empty matrice, so a single point can't be stored condensed.
+        pub fn from_condensed(values: Vec<T>) -> Result<DistanceMatrice<T>, MatriceError> {
+            if values.is_empty() {
+                return Err(MatriceError::Empty);
+            }
+            let n = match condensed_points(values.len()) {
+                Some(n) => n,
+                None => return Err(MatriceError::CondensedLength { len: values.len() }),
+            };
+            if n > u32::MAX as usize {
+                return Err(MatriceError::TooLarge { len: n });
+            }
+            return Ok(DistanceMatrice {
+                storage: Storage::Condensed {
+                    n,
+                    values,
+                    zero: T::default(),
+                },
+            });
+        }
+
+        ///Return the same distances stored as a condensed vector, see `from_condensed`
+        ///
+        /// The diagonal and lower triangle of the matrice are dropped. A single point has no distance to store, its matrice is returned as it is.
+        pub fn into_condensed(self) -> DistanceMatrice<T> {
+            if self.is_condensed() || self.len() < 2 {
+                return self;
+            }
+            return DistanceMatrice {
+                storage: Storage::Condensed {
+                    n: self.len(),
+                    values: self.to_condensed(),
+                    zero: T::default(),
+                },
+            };
+        }
+
+        ///Create a `DistanceMatrice`, checking the given matrice can be used as one
+        ///
+        /// The matrice must be non empty, square with a zero (`T::default()`) diagonal. Only the upper triangle is read, so the lower one can be left to zero, otherwise it must be equal to the upper one. Use `symmetrized` if the triangles differ.
+        pub fn new_checked(distance_mat: Vec<Vec<T>>) -> Result<DistanceMatrice<T>, MatriceError> {
+            DistanceMatrice::check_shape(&distance_mat)?;
+            return DistanceMatrice::checked_symmetry(distance_mat);
+        }
+
+        ///Create a `DistanceMatrice` of similarities, checking it as `new_checked` does but for its diagonal, which `Similarity` doesn't read
+        pub fn new_checked_similarities(
+            distance_mat: Vec<Vec<T>>,
+        ) -> Result<DistanceMatrice<T>, MatriceError> {
+            check_square(&distance_mat)?;
+            return DistanceMatrice::checked_symmetry(distance_mat);
+        }
+
+        /// Check the lower triangle is either left to zero or equal to the upper one
+        fn checked_symmetry(distance_mat: Vec<Vec<T>>) -> Result<DistanceMatrice<T>, MatriceError> {
+            let n = distance_mat.len();
+            let lower_filled =
+                (0..n).any(|i| distance_mat[i][..i].iter().any(|&d| d != T::default()));
+            if lower_filled {
+                let asymmetric = (0..n)
+                    .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                    .find(|&(i, j)| distance_mat[i][j] != distance_mat[j][i]);
+                if let Some((i, j)) = asymmetric {
+                    return Err(MatriceError::Asymmetric { i, j });
+                }
+            }
+            return Ok(DistanceMatrice {
+                storage: Storage::Full(distance_mat),
+            });
+        }
+
+        /// Check the matrice is square with a zero diagonal
+        fn check_shape(distance_mat: &[Vec<T>]) -> Result<(), MatriceError> {
+            check_square(distance_mat)?;
+            for (row, line) in distance_mat.iter().enumerate() {
+                if line[row] != T::default() {
+                    return Err(MatriceError::NonZeroDiagonal { i: row });
                 }
             }
-            return true;
+            return Ok(());
+        }
+    }
+
+    impl<T: Ord + Copy + Default + Mean> DistanceMatrice<T> {
+        ///Create a `DistanceMatrice` from a square matrice with a zero diagonal whose two triangles may differ, `from` telling how to compute each distance from them
+        ///
+        /// Both triangles of the new matrice hold the computed distances.
+        pub fn symmetrized(
+            mut distance_mat: Vec<Vec<T>>,
+            from: Symmetrize,
+        ) -> Result<DistanceMatrice<T>, MatriceError> {
+            DistanceMatrice::check_shape(&distance_mat)?;
+
+            let n = distance_mat.len();
+            for (i, j) in (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))) {
+                let d_ij = from.apply(distance_mat[i][j], distance_mat[j][i]);
+                distance_mat[i][j] = d_ij;
+                distance_mat[j][i] = d_ij;
+            }
+            return Ok(DistanceMatrice {
+                storage: Storage::Full(distance_mat),
+            });
+        }
+    }
+
+    /// Only checks the matrice is non empty, square and not too large, see `DistanceMatrice::new_checked` to check its values as well
+    impl<T> TryFrom<Vec<Vec<T>>> for DistanceMatrice<T> {
+        type Error = MatriceError;
+
+        fn try_from(distance_mat: Vec<Vec<T>>) -> Result<DistanceMatrice<T>, MatriceError> {
+            check_square(&distance_mat)?;
+            return Ok(DistanceMatrice {
+                storage: Storage::Full(distance_mat),
+            });
         }
+    }
 
+    impl<T: Copy + fmt::Display> DistanceMatrice<T> {
         ///Display a `DistanceMatrice`
         pub fn display_mat(&self) {
             let mut complete_str = String::new();
 
             let mut max_len = 0;
 
-            for line in &self.distance_mat {
+            let rows = self.to_rows();
+            for line in &rows {
                 for elem in line {
                     if elem.to_string().len() > max_len {
                         max_len = elem.to_string().len();
@@ -333,14 +1128,10 @@ pub mod robinson {
 
             let width = max_len + 1;
 
-            for line in &self.distance_mat {
+            for line in &rows {
                 let mut line_str = String::new();
                 for col in line {
-                    line_str = format!(
-                        "{}{}",
-                        line_str,
-                        format!("{:width$}", col.to_string(), width = width)
-                    );
+                    line_str = format!("{}{:width$}", line_str, col.to_string(), width = width);
                 }
                 complete_str = format!("{}\n{}", complete_str, line_str);
             }
@@ -350,14 +1141,13 @@ pub mod robinson {
 
     #[test]
     fn test_is_robinson_true() {
-        let dist_mat = DistanceMatrice {
-            distance_mat: vec![
-                vec![0, 1, 2, 4],
-                vec![0, 0, 2, 3],
-                vec![0, 0, 0, 1],
-                vec![0, 0, 0, 0],
-            ],
-        };
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 2, 3],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
         assert!(dist_mat.is_robinson());
     }
 
@@ -371,8 +1161,101 @@ pub mod robinson {
         ];
 
         let rob = Robin::new(mat);
-
-        assert_eq!(rob.refine(1, vec![2, 3, 4]), vec![vec![4, 2], vec![3]]);
+        let mut buckets = Buckets::new(4);
+        let (mut keyed, mut class_ends) = (Vec::new(), Vec::new());
+        let classes_of = |keyed: &[(u32, u32)], class_ends: &[usize]| {
+            let mut class_start = 0;
+            let mut classes: Vec<Vec<u32>> = Vec::new();
+            for &class_end in class_ends {
+                classes.push(
+                    keyed[class_start..class_end]
+                        .iter()
+                        .map(|&(_, x)| x)
+                        .collect(),
+                );
+                class_start = class_end;
+            }
+            return classes;
+        };
+
+        assert_eq!(
+            rob.refine(
+                0,
+                vec![1, 2, 3].into_iter(),
+                &mut buckets,
+                &mut keyed,
+                &mut class_ends
+            ),
+            2
+        );
+        assert_eq!(classes_of(&keyed, &class_ends), vec![vec![3, 1], vec![2]]);
+
+        // Distances 4, 5, 5 and 6 are a chain within the tolerance, but 6 is more than the tolerance away from 4 and starts a new class
+        let rob = Robin::new(vec![
+            vec![0, 5, 4, 6, 5],
+            vec![0, 0, 1, 1, 1],
+            vec![0, 0, 0, 1, 1],
+            vec![0, 0, 0, 0, 1],
+            vec![0, 0, 0, 0, 0],
+        ])
+        .with_tolerance(1);
+        let mut buckets = Buckets::new(5);
+        assert_eq!(
+            rob.refine(
+                0,
+                vec![1, 2, 3, 4].into_iter(),
+                &mut buckets,
+                &mut keyed,
+                &mut class_ends
+            ),
+            2
+        );
+        assert_eq!(
+            classes_of(&keyed, &class_ends),
+            vec![vec![2, 4, 1], vec![3]]
+        );
+
+        // Distances equal only within the tolerance are a single class, left unsorted as exactly equal ones
+        for s_list in [vec![1, 2, 4], vec![2, 4, 1]] {
+            assert_eq!(
+                rob.refine(
+                    0,
+                    s_list.iter().copied(),
+                    &mut buckets,
+                    &mut keyed,
+                    &mut class_ends
+                ),
+                1
+            );
+            let points: Vec<u32> = keyed.iter().map(|&(_, x)| x).collect();
+            assert_eq!(points, s_list.iter().rev().copied().collect::<Vec<u32>>());
+        }
+        assert_eq!(
+            rob.refine(
+                1,
+                vec![2, 3].into_iter(),
+                &mut buckets,
+                &mut keyed,
+                &mut class_ends
+            ),
+            1
+        );
+        assert_eq!(keyed, vec![(1, 3), (1, 2)]);
+    }
+
+    #[test]
+    fn test_sort_by_rank() {
+        // 600 points on a line, seen from 300 : ranks go over a byte, and every distance but 300 is reached from both sides
+        let on_line = OnLine((0..600).collect());
+        let mut buckets = Buckets::new(600);
+        let mut keyed: Vec<(u64, u32)> = (0..600)
+            .map(|x| (x * 7 + 11) % 600)
+            .map(|x| (on_line.d(300, x), x))
+            .collect();
+        let mut expected = keyed.clone();
+        expected.sort_by_key(|&(d_qx, _)| d_qx);
+        buckets.sort_by_rank(&on_line, 300, &mut keyed);
+        assert_eq!(keyed, expected);
     }
 
     #[test]
@@ -387,40 +1270,40 @@ pub mod robinson {
         let rob = Robin::new(mat);
 
         assert_eq!(
-            rob.recursive_refine(1, vec![1], vec![4, 3, 2], vec![]),
-            vec![vec![2, 3, 4]]
+            rob.recursive_refine(&mut Buckets::new(4), 0, vec![0], vec![3, 2, 1], vec![]),
+            vec![vec![1, 2, 3]]
         );
     }
 
     #[test]
     fn test_d() {
-        let dist_mat = DistanceMatrice {
-            distance_mat: vec![
-                vec![0, 1, 2, 4],
-                vec![0, 0, 3, 3],
-                vec![0, 0, 0, 1],
-                vec![0, 0, 0, 0],
-            ],
-        };
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 3, 3],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
 
-        assert_eq!(dist_mat.d(1, 3), 2);
-        assert_eq!(dist_mat.d(3, 1), 2);
+        assert_eq!(dist_mat.d(0, 2), 2);
+        assert_eq!(dist_mat.d(2, 0), 2);
     }
 
     #[test]
     fn test_permut_matrice() {
-        let dist_mat = DistanceMatrice {
-            distance_mat: vec![
-                vec![0, 2, 2, 3, 4],
-                vec![0, 0, 1, 1, 3],
-                vec![0, 0, 0, 1, 2],
-                vec![0, 0, 0, 0, 1],
-                vec![0, 0, 0, 0, 0],
-            ],
-        };
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 2, 2, 3, 4],
+            vec![0, 0, 1, 1, 3],
+            vec![0, 0, 0, 1, 2],
+            vec![0, 0, 0, 0, 1],
+            vec![0, 0, 0, 0, 0],
+        ])
+        .unwrap();
 
         assert_eq!(
-            dist_mat.permut_matrice(&vec![4, 5, 3, 2, 1]).distance_mat,
+            dist_mat
+                .permut_matrice(&Permutation::try_from(vec![3, 4, 2, 1, 0]).unwrap())
+                .to_rows(),
             vec![
                 vec![0, 1, 1, 1, 3],
                 vec![0, 0, 2, 3, 4],
@@ -431,22 +1314,877 @@ pub mod robinson {
         )
     }
 
+    #[test]
+    fn test_condensed() {
+        let rows = vec![
+            vec![0, 2, 2, 3, 4],
+            vec![0, 0, 1, 1, 3],
+            vec![0, 0, 0, 1, 2],
+            vec![0, 0, 0, 0, 1],
+            vec![0, 0, 0, 0, 0],
+        ];
+        let full = DistanceMatrice::try_from(rows.clone()).unwrap();
+        let condensed =
+            DistanceMatrice::from_condensed(vec![2, 2, 3, 4, 1, 1, 3, 1, 2, 1]).unwrap();
+        assert!(condensed.is_condensed());
+        assert_eq!(condensed.len(), 5);
+        assert_eq!(condensed.to_rows(), rows);
+        assert_eq!(condensed.to_condensed(), full.to_condensed());
+        assert_eq!(condensed.d(3, 1), 1);
+        assert_eq!(condensed.d(2, 2), 0);
+
+        let permut = Permutation::try_from(vec![3, 4, 2, 1, 0]).unwrap();
+        let permuted = condensed.permut_matrice(&permut);
+        assert!(permuted.is_condensed());
+        assert_eq!(permuted.to_rows(), full.permut_matrice(&permut).to_rows());
+        assert_eq!(condensed.is_robinson(), full.is_robinson());
+        assert_eq!(
+            permuted.find_violation(),
+            full.permut_matrice(&permut).find_violation()
+        );
+
+        assert!(full.into_condensed().is_condensed());
+        assert_eq!(
+            DistanceMatrice::from_condensed(vec![1, 2]).err(),
+            Some(MatriceError::CondensedLength { len: 2 })
+        );
+        assert_eq!(
+            DistanceMatrice::<u32>::from_condensed(vec![]).err(),
+            Some(MatriceError::Empty)
+        );
+        assert!(matches!(
+            Robin::<u32>::try_new(vec![]),
+            Err(MatriceError::Empty)
+        ));
+        let single = DistanceMatrice::try_from(vec![vec![0]]).unwrap();
+        assert!(!single.into_condensed().is_condensed());
+
+        for seed in 0..20 {
+            let mat = get_shuffled_robinson_matrice(2 + seed as usize % 12, seed);
+            let rob = Robin::new(mat.clone());
+            let condensed = DistanceMatrice::try_from(mat).unwrap().into_condensed();
+            let rob_condensed = Robin::from(condensed);
+            assert_eq!(rob_condensed.solve(), rob.solve());
+        }
+    }
+
+    #[test]
+    fn test_permuted_view() {
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 2, 2, 3, 4],
+            vec![0, 0, 1, 1, 3],
+            vec![0, 0, 0, 1, 2],
+            vec![0, 0, 0, 0, 1],
+            vec![0, 0, 0, 0, 0],
+        ])
+        .unwrap();
+        let permut = Permutation::try_from(vec![3, 4, 2, 1, 0]).unwrap();
+        let view = dist_mat.permuted(&permut);
+        let permuted = dist_mat.permut_matrice(&permut);
+        for i in 0..5 {
+            for j in 0..5 {
+                assert_eq!(view.d(i, j), permuted.d(i, j));
+            }
+        }
+        assert_eq!(view.to_matrice().to_rows(), permuted.to_rows());
+
+        for seed in 0..20 {
+            let dist_mat =
+                DistanceMatrice::try_from(get_shuffled_robinson_matrice(6, seed)).unwrap();
+            for permut in
+                Robin::new(get_shuffled_robinson_matrice(6, seed + 20)).compatible_orders()
+            {
+                let view = dist_mat.permuted(&permut);
+                let permuted = dist_mat.permut_matrice(&permut);
+                assert_eq!(view.is_robinson(), permuted.is_robinson());
+                assert_eq!(view.robinson_violations(), permuted.robinson_violations());
+                assert_eq!(
+                    view.find_violation_with_tolerance(1),
+                    permuted.find_violation_with_tolerance(1)
+                );
+            }
+        }
+
+        assert_eq!(
+            dist_mat.try_permuted(&Permutation::identity(2)).err(),
+            Some(MatriceError::PermutationLength {
+                len: 2,
+                expected: 5
+            })
+        );
+    }
+
+    /// Points on a line, distances computed on the fly
+    #[cfg(test)]
+    struct OnLine(Vec<i64>);
+
+    #[cfg(test)]
+    impl Dissimilarity for OnLine {
+        type Distance = u64;
+
+        fn len(&self) -> usize {
+            return self.0.len();
+        }
+
+        fn d(&self, i: u32, j: u32) -> u64 {
+            return self.0[i as usize].abs_diff(self.0[j as usize]);
+        }
+    }
+
+    #[test]
+    fn test_dissimilarity() {
+        let on_line = OnLine(vec![7, -3, 12, 0, 5, 2]);
+        let rob = Robin::from(on_line);
+        let order = rob.solve().unwrap();
+        let mut positions: Vec<i64> = order
+            .permut
+            .iter()
+            .map(|&x| rob.dist.0[x as usize])
+            .collect();
+        if positions[0] > positions[1] {
+            positions.reverse();
+        }
+        assert_eq!(positions, vec![-3, 0, 2, 5, 7, 12]);
+        assert!(PermutedView::new(&rob.dist, &order.permut)
+            .unwrap()
+            .is_robinson());
+        assert!(!PermutedView::new(&rob.dist, &Permutation::identity(6))
+            .unwrap()
+            .is_robinson());
+        assert_eq!(rob.count_compatible_orders(), 2);
+
+        // A view is a dissimilarity too
+        let dist_mat = DistanceMatrice::try_from(get_shuffled_robinson_matrice(8, 1)).unwrap();
+        let shuffle = Permutation::try_from(vec![5, 2, 7, 0, 1, 6, 4, 3]).unwrap();
+        let rob = Robin::from(dist_mat.permuted(&shuffle));
+        let order = rob.solve().unwrap();
+        assert!(dist_mat
+            .permuted(&shuffle.compose(&order.permut).unwrap())
+            .is_robinson());
+    }
+
     #[test]
     fn test_is_robinson_false() {
-        let dist_mat = DistanceMatrice {
-            distance_mat: vec![
-                vec![0, 1, 2, 4],
-                vec![0, 0, 3, 3],
-                vec![0, 0, 0, 1],
-                vec![0, 0, 0, 0],
-            ],
-        };
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 3, 3],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
         assert!(!dist_mat.is_robinson());
     }
 
+    #[test]
+    fn test_find_violation() {
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 3, 3],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
+        assert_eq!(
+            dist_mat.find_violation(),
+            Some(Violation {
+                i: 0,
+                neighbour: 1,
+                j: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_robinson_violations() {
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 3, 3],
+            vec![0, 0, 0, 5],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
+        let report = dist_mat.robinson_violations();
+        assert_eq!(
+            report.violations,
+            vec![
+                MeasuredViolation {
+                    violation: Violation {
+                        i: 0,
+                        neighbour: 1,
+                        j: 2
+                    },
+                    magnitude: 1
+                },
+                MeasuredViolation {
+                    violation: Violation {
+                        i: 1,
+                        neighbour: 2,
+                        j: 3
+                    },
+                    magnitude: 2
+                },
+            ]
+        );
+        assert_eq!(report.per_row, vec![1, 1, 1, 1]);
+        assert_eq!(report.max_magnitude(), Some(2));
+        assert_eq!(
+            report.violations[0].violation,
+            dist_mat.find_violation().unwrap()
+        );
+        assert_eq!(dist_mat.robinson_violations_with_tolerance(1).len(), 1);
+    }
+
+    #[test]
+    fn test_new_checked() {
+        let upper_only = vec![vec![0, 1, 2], vec![0, 0, 3], vec![0, 0, 0]];
+        assert!(DistanceMatrice::new_checked(upper_only).is_ok());
+
+        let symmetric = vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]];
+        assert!(DistanceMatrice::new_checked(symmetric).is_ok());
+
+        let asymmetric = vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 4, 0]];
+        assert_eq!(
+            DistanceMatrice::new_checked(asymmetric).err(),
+            Some(MatriceError::Asymmetric { i: 1, j: 2 })
+        );
+
+        let diagonal = vec![vec![0, 1], vec![0, 5]];
+        assert_eq!(
+            DistanceMatrice::new_checked(diagonal).err(),
+            Some(MatriceError::NonZeroDiagonal { i: 1 })
+        );
+
+        let ragged = vec![vec![0, 1, 2], vec![0, 0], vec![0, 0, 0]];
+        assert_eq!(
+            DistanceMatrice::new_checked(ragged).err(),
+            Some(MatriceError::NotSquare {
+                row: 1,
+                len: 2,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(Robin::try_new(vec![vec![0, 1], vec![0, 0]]).is_ok());
+        assert_eq!(
+            Robin::try_new(vec![vec![0, 1], vec![0]]).err(),
+            Some(MatriceError::NotSquare {
+                row: 1,
+                len: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            Robin::<u32>::try_new(vec![]).err(),
+            Some(MatriceError::Empty)
+        );
+        assert_eq!(
+            DistanceMatrice::try_from(vec![vec![0, 1, 2]]).err(),
+            Some(MatriceError::NotSquare {
+                row: 0,
+                len: 3,
+                expected: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_permut_matrice() {
+        let dist_mat = DistanceMatrice::try_from(vec![vec![0, 1], vec![0, 0]]).unwrap();
+        assert!(dist_mat
+            .try_permut_matrice(&Permutation::identity(2))
+            .is_ok());
+        assert_eq!(
+            dist_mat.try_permut_matrice(&Permutation::identity(1)).err(),
+            Some(MatriceError::PermutationLength {
+                len: 1,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_symmetrized() {
+        let mat = vec![vec![0, 1, 2], vec![3, 0, 6], vec![2, 4, 0]];
+        let expected = [
+            (Symmetrize::Upper, [1, 2, 6]),
+            (Symmetrize::Lower, [3, 2, 4]),
+            (Symmetrize::Min, [1, 2, 4]),
+            (Symmetrize::Max, [3, 2, 6]),
+            (Symmetrize::Mean, [2, 2, 5]),
+        ];
+        for (from, [d_01, d_02, d_12]) in expected {
+            let dist = DistanceMatrice::symmetrized(mat.clone(), from).unwrap();
+            assert_eq!(
+                dist.to_rows(),
+                vec![
+                    vec![0, d_01, d_02],
+                    vec![d_01, 0, d_12],
+                    vec![d_02, d_12, 0]
+                ]
+            );
+        }
+
+        let rob = Robin::from(DistanceMatrice::symmetrized(mat, Symmetrize::Min).unwrap());
+        assert!(rob.solve().is_ok());
+    }
+
+    #[test]
+    fn test_solve_robinson() {
+        let mat = vec![
+            vec![0, 1, 2, 4, 3],
+            vec![0, 0, 1, 3, 1],
+            vec![0, 0, 0, 2, 1],
+            vec![0, 0, 0, 0, 2],
+            vec![0, 0, 0, 0, 0],
+        ];
+        let rob = Robin::new(mat);
+
+        let order = rob.solve().unwrap();
+        assert!(rob.dist.permut_matrice(&order.permut).is_robinson());
+    }
+
+    #[test]
+    fn test_solve_not_robinson() {
+        // Four points on a cycle
+        let mat = vec![
+            vec![0, 1, 2, 1],
+            vec![0, 0, 1, 2],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ];
+        let mut rob = Robin::new(mat);
+
+        let not_robinson = rob.solve().unwrap_err();
+        let mut sorted_permut = not_robinson.permut.clone().into_vec();
+        sorted_permut.sort();
+        assert_eq!(sorted_permut, vec![0, 1, 2, 3]);
+
+        let Violation { i, neighbour, j } = not_robinson.violation;
+        assert!(rob.dist.d(i, j) < rob.dist.d(i, neighbour).max(rob.dist.d(neighbour, j)));
+
+        assert!(!rob.resolve_robin());
+    }
+
+    #[test]
+    fn test_solve_float() {
+        let mat: Vec<Vec<OrdFloat<f64>>> = vec![
+            vec![0.0, 0.5, 1.5, 2.5],
+            vec![0.0, 0.0, 2.5, 0.5],
+            vec![0.0, 0.0, 0.0, 2.5],
+            vec![0.0, 0.0, 0.0, 0.0],
+        ]
+        .into_iter()
+        .map(|line| line.into_iter().map(OrdFloat).collect())
+        .collect();
+        let rob = Robin::new(mat);
+
+        let order = rob.solve().unwrap();
+        assert!(rob.dist.permut_matrice(&order.permut).is_robinson());
+    }
+
+    #[test]
+    fn test_solve_u64() {
+        let big = u32::MAX as u64;
+        let mat: Vec<Vec<u64>> = vec![
+            vec![0, big + 1, big + 2, big + 4],
+            vec![0, 0, big + 3, big + 3],
+            vec![0, 0, 0, big + 1],
+            vec![0, 0, 0, 0],
+        ];
+        let rob = Robin::new(mat);
+
+        assert!(rob.solve().is_err());
+        assert!(!rob.dist.is_robinson());
+    }
+
+    #[test]
+    fn test_solve_with_tolerance() {
+        // Four points on a cycle, up to rounding noise on the diagonals
+        let noisy = 1.0 + 1e-12;
+        let mat: Vec<Vec<OrdFloat<f64>>> = vec![
+            vec![0.0, 1.0, noisy, 1.0],
+            vec![0.0, 0.0, 1.0, noisy],
+            vec![0.0, 0.0, 0.0, 1.0],
+            vec![0.0, 0.0, 0.0, 0.0],
+        ]
+        .into_iter()
+        .map(|line| line.into_iter().map(OrdFloat).collect())
+        .collect();
+
+        assert!(Robin::new(mat.clone()).solve().is_err());
+
+        let rob = Robin::new(mat).with_tolerance(OrdFloat(1e-9));
+        assert_eq!(rob.tolerance(), Some(OrdFloat(1e-9)));
+        let order = rob.solve().unwrap();
+        let permuted = rob.dist.permut_matrice(&order.permut);
+        assert!(permuted.is_robinson_with_tolerance(OrdFloat(1e-9)));
+    }
+
+    #[test]
+    fn test_solve_with_tolerance_classes() {
+        // From 0, distances 1, 2 and 3 are a chain within the tolerance. Grouping the whole chain made 1 and 3 a single class and missed the order 2 0 1 3
+        let rob = Robin::new(vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 1, 1],
+            vec![2, 1, 0, 4],
+            vec![3, 1, 4, 0],
+        ])
+        .with_tolerance(1);
+        let order = rob.solve().unwrap();
+        let permuted = rob.dist.permut_matrice(&order.permut);
+        assert!(permuted.is_robinson_with_tolerance(1));
+    }
+
+    #[test]
+    fn test_find_violation_with_tolerance() {
+        let dist_mat = DistanceMatrice::try_from(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 0, 3, 3],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ])
+        .unwrap();
+        assert!(dist_mat.is_robinson_with_tolerance(1));
+        assert_eq!(
+            dist_mat.find_violation_with_tolerance(0),
+            dist_mat.find_violation()
+        );
+    }
+
+    #[test]
+    fn test_mmodule_tree() {
+        let mat = vec![
+            vec![0, 1, 2, 4, 3],
+            vec![0, 0, 1, 3, 1],
+            vec![0, 0, 0, 2, 1],
+            vec![0, 0, 0, 0, 2],
+            vec![0, 0, 0, 0, 0],
+        ];
+        let rob = Robin::new(mat);
+
+        let tree = rob.mmodule_tree().unwrap();
+        assert_eq!(tree.pivot, 0);
+        assert_eq!(tree.points, rob.solve().unwrap().permut.into_vec());
+        for node in &tree {
+            // The copoints partition the node without its pivot
+            let mut copoint_points: Vec<u32> = node
+                .copoints
+                .iter()
+                .flat_map(|copoint| copoint.points.clone())
+                .chain([node.pivot])
+                .collect();
+            copoint_points.sort();
+            let mut points = node.points.clone();
+            points.sort();
+            assert_eq!(copoint_points, points);
+        }
+
+        assert!(Robin::<u32>::new(vec![]).mmodule_tree().is_none());
+    }
+
+    #[test]
+    fn test_compatible_orders() {
+        let mat = vec![
+            vec![0, 1, 2, 4, 3],
+            vec![0, 0, 1, 3, 1],
+            vec![0, 0, 0, 2, 1],
+            vec![0, 0, 0, 0, 2],
+            vec![0, 0, 0, 0, 0],
+        ];
+        let rob = Robin::new(mat);
+
+        let orders: Vec<Permutation> = rob.compatible_orders().collect();
+        assert_eq!(orders.len() as u128, rob.count_compatible_orders());
+        for order in &orders {
+            assert!(rob.dist.permut_matrice(order).is_robinson());
+        }
+
+        // Four points on a cycle
+        let rob = Robin::new(vec![
+            vec![0, 1, 2, 1],
+            vec![0, 0, 1, 2],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ]);
+        assert_eq!(rob.compatible_orders().count(), 0);
+        assert_eq!(rob.count_compatible_orders(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "up to a tolerance")]
+    fn test_compatible_orders_with_tolerance() {
+        // Robinson only up to the tolerance
+        let rob = Robin::new(vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 1, 1],
+            vec![2, 1, 0, 4],
+            vec![3, 1, 4, 0],
+        ])
+        .with_tolerance(1);
+        assert!(Robin::new(rob.dist.to_rows()).solve().is_err());
+        assert!(rob.solve().is_ok());
+        rob.count_compatible_orders();
+    }
+
+    #[test]
+    fn test_compatible_orders_brute_force() {
+        // Every permutation of small random Robinson matrices with many ties
+        for seed in 0..50 {
+            let n = 2 + seed as usize % 5;
+            let mut mat = get_shuffled_robinson_matrice(n, seed);
+            for (i, line) in mat.iter_mut().enumerate() {
+                for d_ij in &mut line[i + 1..] {
+                    *d_ij = (*d_ij + seed as u32 % 3) / 2;
+                }
+            }
+            let rob = Robin::new(mat);
+
+            let mut expected: Vec<Vec<u32>> = Vec::new();
+            let mut permut: Vec<u32> = (0..n as u32).collect();
+            loop {
+                let order = Permutation::try_from(permut.clone()).unwrap();
+                if rob.dist.permut_matrice(&order).is_robinson() {
+                    expected.push(permut.clone());
+                }
+                // Next permutation in lexicographic order
+                let Some(k) = (1..n).rev().find(|&k| permut[k - 1] < permut[k]) else {
+                    break;
+                };
+                let l = (k..n).rev().find(|&l| permut[l] > permut[k - 1]).unwrap();
+                permut.swap(k - 1, l);
+                permut[k..].reverse();
+            }
+
+            let mut orders: Vec<Vec<u32>> = rob.compatible_orders().map(Vec::from).collect();
+            orders.sort();
+            assert_eq!(orders, expected);
+            assert_eq!(rob.count_compatible_orders(), expected.len() as u128);
+        }
+    }
+
+    #[test]
+    fn test_obstruction() {
+        // Four points on a cycle, along with a fifth point far from all of them
+        let rob = Robin::new(vec![
+            vec![0, 1, 2, 1, 5],
+            vec![0, 0, 1, 2, 5],
+            vec![0, 0, 0, 1, 5],
+            vec![0, 0, 0, 0, 5],
+            vec![0, 0, 0, 0, 0],
+        ]);
+        let obstruction = rob.obstruction().unwrap();
+        assert_eq!(obstruction.points, vec![0, 1, 2, 3]);
+        assert!(obstruction.forbidden.contains(&Violation {
+            i: 0,
+            neighbour: 2,
+            j: 1
+        }));
+
+        assert!(Robin::new(get_shuffled_robinson_matrice(8, 3))
+            .obstruction()
+            .is_none());
+    }
+
+    #[test]
+    fn test_obstruction_minimal() {
+        for seed in 0..30 {
+            let mut mat = get_shuffled_robinson_matrice(7, seed);
+            mat[seed as usize % 3][4 + seed as usize % 3] = 0;
+            let rob = Robin::new(mat);
+            let obstruction = match rob.obstruction() {
+                Some(obstruction) => obstruction,
+                None => continue,
+            };
+            let points = &obstruction.points;
+
+            assert!(!rob.admits_compatible_order(points));
+            for k in 0..points.len() {
+                let remaining = [&points[..k], &points[k + 1..]].concat();
+                assert!(rob.admits_compatible_order(&remaining));
+            }
+
+            // Every order of the points puts some neighbour between its i and j
+            let mut order = points.clone();
+            loop {
+                let position = |x: u32| order.iter().position(|&y| y == x).unwrap();
+                assert!(obstruction.forbidden.iter().any(|v| {
+                    let (i, neighbour, j) = (position(v.i), position(v.neighbour), position(v.j));
+                    i.min(j) < neighbour && neighbour < i.max(j)
+                }));
+                let Some(k) = (1..order.len()).rev().find(|&k| order[k - 1] < order[k]) else {
+                    break;
+                };
+                let l = (k..order.len())
+                    .rev()
+                    .find(|&l| order[l] > order[k - 1])
+                    .unwrap();
+                order.swap(k - 1, l);
+                order[k..].reverse();
+            }
+        }
+    }
+
+    /// Shuffled Robinson matrice of size n, d(i, j) only depends on |i - j| in the hidden order
+    #[cfg(test)]
+    fn get_shuffled_robinson_matrice(n: usize, seed: u64) -> Vec<Vec<u32>> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            return state >> 33;
+        };
+        let mut hidden_order: Vec<usize> = (0..n).collect();
+        for i in (1..n).rev() {
+            hidden_order.swap(i, (next() % (i as u64 + 1)) as usize);
+        }
+
+        let mut matrice = vec![vec![0; n]; n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let (x, y) = (hidden_order[i], hidden_order[j]);
+                matrice[x.min(y)][x.max(y)] = ((j - i) / 3) as u32 + 1;
+            }
+        }
+        return matrice;
+    }
+
+    /// Positions 0 to n - 1 shuffled, points on a line nest copoints as deep as there are points
+    #[cfg(test)]
+    fn shuffled_line(n: u16) -> Vec<u16> {
+        let mut state: u64 = 7;
+        let mut positions: Vec<u16> = (0..n).collect();
+        for i in (1..n as usize).rev() {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            positions.swap(i, ((state >> 33) % (i as u64 + 1)) as usize);
+        }
+        return positions;
+    }
+
+    /// Solve on a thread with the default stack of 2 MiB
+    #[cfg(test)]
+    fn solve_on_default_stack<D: Dissimilarity + Sync>(rob: &Robin<D::Distance, D>) -> bool
+    where
+        D::Distance: Default + Send + Sync,
+    {
+        return std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(2 * 1024 * 1024)
+                .spawn_scoped(scope, || rob.solve().is_ok())
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+    }
+
+    #[test]
+    fn test_solve_deep_copoints_on_default_stack() {
+        // The recursive resolution overflowed from 2000 points on a line
+        let positions = shuffled_line(3000).into_iter().map(i64::from).collect();
+        assert!(solve_on_default_stack(&Robin::from(OnLine(positions))));
+    }
+
+    #[test]
+    #[ignore = "needs 400 MB and about 20 s in release mode"]
+    fn test_solve_large_on_default_stack() {
+        // A generated Robinson matrice of 20000 points on a line, stored condensed. Run it with
+        // cargo test --release -- --ignored test_solve_large_on_default_stack
+        let positions = shuffled_line(20000);
+        let mut condensed: Vec<u16> = Vec::with_capacity(20000 * 19999 / 2);
+        for (i, &x) in positions.iter().enumerate() {
+            condensed.extend(positions[i + 1..].iter().map(|&y| x.abs_diff(y)));
+        }
+        let rob = Robin::from(DistanceMatrice::from_condensed(condensed).unwrap());
+        assert!(solve_on_default_stack(&rob));
+    }
+
     #[derive(Clone)]
     struct VecPoint {
         point: u32,
         vec: Vec<u32>,
     }
+
+    /// A call of `recursive_refine` : S as a segment of the arena, in and out as heads of pivot lists
+    struct RefineCall {
+        in_head: usize,
+        s_segment: Segment,
+        out_head: usize,
+    }
+
+    /// A pending call of `recursive_refine` whose S' was split by the pivot : S' is stored in the arena from `base`, class after class, and the calls on each class are still to be made
+    struct RefineFrame {
+        base: usize,
+        pivots_len: usize,
+        class_ends: Vec<usize>,
+        in_list_new: usize,
+        out_list_new: usize,
+        next: usize,
+    }
+
+    /// Consecutive points of the refinement arena, read backward if `reversed`
+    #[derive(Clone, Copy)]
+    struct Segment {
+        start: usize,
+        len: usize,
+        reversed: bool,
+    }
+
+    impl Segment {
+        fn get(&self, arena: &[u32], k: usize) -> u32 {
+            if self.reversed {
+                return arena[self.start + self.len - 1 - k];
+            }
+            return arena[self.start + k];
+        }
+
+        fn points<'a>(&self, arena: &'a [u32]) -> impl DoubleEndedIterator<Item = u32> + 'a {
+            let segment = *self;
+            return (0..self.len).map(move |k| segment.get(arena, k));
+        }
+
+        fn reversed(&self) -> Segment {
+            return Segment {
+                reversed: !self.reversed,
+                ..*self
+            };
+        }
+    }
+
+    /// End of a pivot list
+    const NIL: usize = usize::MAX;
+
+    /// Linked lists of segments. A list is the index of its first node, lists built from a common one share its nodes
+    struct PivotLists {
+        nodes: Vec<(Segment, usize)>,
+    }
+
+    impl PivotLists {
+        fn new() -> PivotLists {
+            return PivotLists { nodes: Vec::new() };
+        }
+
+        fn len(&self) -> usize {
+            return self.nodes.len();
+        }
+
+        fn truncate(&mut self, len: usize) {
+            self.nodes.truncate(len);
+        }
+
+        /// Return the list made of `segment` followed by `next`
+        fn push(&mut self, segment: Segment, next: usize) -> usize {
+            if segment.len == 0 {
+                return next;
+            }
+            self.nodes.push((segment, next));
+            return self.nodes.len() - 1;
+        }
+
+        /// Return the first point of the non empty list `head`, and the list of the remaining points
+        fn pop(&mut self, arena: &[u32], head: usize) -> (u32, usize) {
+            let (segment, next) = self.nodes[head];
+            let first = segment.get(arena, 0);
+            let rest = Segment {
+                start: if segment.reversed {
+                    segment.start
+                } else {
+                    segment.start + 1
+                },
+                len: segment.len - 1,
+                reversed: segment.reversed,
+            };
+            return (first, self.push(rest, next));
+        }
+    }
+
+    /// Ranks of the distances in the rows of the pivots which split a class, and a buffer to bucket points by these ranks
+    struct Buckets<T> {
+        ranks: Vec<Vec<u32>>,
+        scratch: Vec<(T, u32)>,
+    }
+
+    impl<T: Ord + Copy> Buckets<T> {
+        fn new(n: usize) -> Buckets<T> {
+            return Buckets {
+                ranks: vec![Vec::new(); n],
+                scratch: Vec::new(),
+            };
+        }
+
+        /// Rank the distances from `q`, equal distances get the same rank. Each row is ranked the first time it splits a class, by sorting it once
+        fn rank_row<D: Dissimilarity<Distance = T>>(&mut self, dist: &D, q: u32) {
+            if !self.ranks[q as usize].is_empty() {
+                return;
+            }
+            let mut row: Vec<(T, u32)> =
+                (0..dist.len() as u32).map(|x| (dist.d(q, x), x)).collect();
+            row.sort_unstable();
+            let mut ranks = vec![0; row.len()];
+            let mut rank = 0;
+            for k in 1..row.len() {
+                if row[k].0 != row[k - 1].0 {
+                    rank += 1;
+                }
+                ranks[row[k].1 as usize] = rank;
+            }
+            self.ranks[q as usize] = ranks;
+        }
+
+        /// Sort `keyed` by increasing distance to `q`, keeping the order of points at the same distance. The points are put in buckets by rank, one byte of rank at a time, so each pass takes O(|keyed|)
+        fn sort_by_rank<D: Dissimilarity<Distance = T>>(
+            &mut self,
+            dist: &D,
+            q: u32,
+            keyed: &mut [(T, u32)],
+        ) {
+            self.rank_row(dist, q);
+            let ranks = &self.ranks[q as usize];
+            let rank_of = |&(_, x): &(T, u32)| ranks[x as usize];
+            let min_rank = keyed.iter().map(rank_of).min().unwrap_or(0);
+            let max_rank = keyed.iter().map(rank_of).max().unwrap_or(0);
+            let mut shift = 0;
+            while shift == 0 || (shift < u32::BITS && (max_rank - min_rank) >> shift > 0) {
+                let bucket =
+                    |point: &(T, u32)| ((rank_of(point) - min_rank) >> shift) as usize & 0xff;
+                let mut bucket_starts = [0; 256];
+                for point in keyed.iter() {
+                    bucket_starts[bucket(point)] += 1;
+                }
+                let mut start = 0;
+                for bucket_start in bucket_starts.iter_mut() {
+                    let len = *bucket_start;
+                    *bucket_start = start;
+                    start += len;
+                }
+                self.scratch.clear();
+                self.scratch.extend_from_slice(keyed);
+                for &point in self.scratch.iter() {
+                    let bucket = bucket(&point);
+                    keyed[bucket_starts[bucket]] = point;
+                    bucket_starts[bucket] += 1;
+                }
+                shift += 8;
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Side {
+        Undecided,
+        Left,
+        Right,
+    }
+
+    /// A pending call of `find_compatible_order` : the copoints of `p` still to order and the compatible orders (and trees, if they are built) already found for the others
+    struct OrderFrame {
+        p: u32,
+        c_list: Vec<Vec<u32>>,
+        c_prime_list: Vec<Vec<u32>>,
+        copoint_trees: Vec<MmoduleTree>,
+    }
 }
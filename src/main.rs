@@ -1,69 +1,350 @@
-extern crate robinson_mmodules;
+#![allow(clippy::needless_return)]
 
-use robinson_mmodules::{DistanceMatrice, Robin};
+extern crate robinson_mmodules;
 
-mod test;
-use test::{fill_matrice, get_empty_distance_matrice, get_random_permutation};
+use robinson_mmodules::{
+    AbsDiff, CsvError, DistanceMatrice, LabeledMatrice, NotRobinson, NpyDistance, NpyError,
+    OrdFloat, Permutation, PhylipError, Robin, Violation,
+};
 
-use std::time::SystemTime;
+mod cli;
+use cli::{Args, Command, Format, USAGE};
 
-use std::thread;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
 
-const SIZE: u32 = 5;
+/// Exit code when the matrice is Robinson, or the command succeeded
+const ROBINSON: u8 = 0;
+/// Exit code when the matrice isn't Robinson
+const NOT_ROBINSON: u8 = 1;
+/// Exit code when the arguments are wrong or the input can't be read
+const INPUT_ERROR: u8 = 2;
 
-const STACK_SIZE: usize = 4 * 1024 * 1024;
+fn main() -> ExitCode {
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("robinson: {error}\n\n{USAGE}");
+            return ExitCode::from(INPUT_ERROR);
+        }
+    };
+    match run(&args) {
+        Ok(code) => ExitCode::from(code),
+        Err(error) => {
+            eprintln!("robinson: {error}");
+            ExitCode::from(INPUT_ERROR)
+        }
+    }
+}
 
-const DISPLAY_INITIAL_MAT: bool = true;
-const DISPLAY_FINAL_MAT: bool = true;
-const DISPLAY_FOUND_PERMUTATION: bool = true;
+fn run(args: &Args) -> Result<u8, Box<dyn Error>> {
+    if args.command == Command::Help {
+        println!("{USAGE}");
+        return Ok(ROBINSON);
+    }
 
-fn main() {
-    // A bigger stack size is required for large matrice
-    let child = thread::Builder::new()
-        .stack_size(STACK_SIZE)
-        .spawn(run)
-        .unwrap();
-    child.join().unwrap();
+    // Integers are read as such so they stay exact, files holding other numbers are read as floats
+    let input = Input::open(args)?;
+    match read_matrice::<i64>(args, &input) {
+        // Integers less than epsilon apart are at most its integer part apart
+        Ok(read) => process(
+            args,
+            &read,
+            args.tolerance.map(|epsilon| epsilon.floor() as i64),
+        ),
+        Err(error) if is_not_integer(error.as_ref()) => {
+            let read = read_matrice::<OrdFloat<f64>>(args, &input)?;
+            process(args, &read, args.tolerance.map(OrdFloat))
+        }
+        Err(error) => Err(error),
+    }
 }
-fn run() {
-    let mut initial_matrice = get_empty_distance_matrice(SIZE);
 
-    initial_matrice = fill_matrice(initial_matrice, 0.005);
+///Run the command on the matrice read, `tolerance` being the one of the arguments as a distance
+fn process<T>(
+    args: &Args,
+    read: &LabeledMatrice<T>,
+    tolerance: Option<T>,
+) -> Result<u8, Box<dyn Error>>
+where
+    T: Ord + Copy + Default + AbsDiff + fmt::Display + NpyDistance,
+{
+    let mut robin = Robin::from(&read.dist);
+    if let Some(epsilon) = tolerance {
+        robin = robin.with_tolerance(epsilon);
+    }
+
+    if args.command == Command::Check && args.in_order {
+        let violation = match tolerance {
+            Some(epsilon) => read.dist.find_violation_with_tolerance(epsilon),
+            None => read.dist.find_violation(),
+        };
+        if !args.quiet {
+            match violation {
+                None => println!("Robinson"),
+                Some(Violation { i, neighbour, j }) => println!(
+                    "distance matrice isn't Robinson : {neighbour} lies between {i} and {j} but d({i}, {j}) < max(d({i}, {neighbour}), d({neighbour}, {j}))"
+                ),
+            }
+        }
+        return Ok(if violation.is_none() {
+            ROBINSON
+        } else {
+            NOT_ROBINSON
+        });
+    }
 
-    let initial_distance_matrice = DistanceMatrice {
-        distance_mat: initial_matrice,
+    let order = match robin.solve() {
+        Ok(order) => order,
+        Err(not_robinson) => {
+            report_not_robinson(args, &not_robinson);
+            return Ok(NOT_ROBINSON);
+        }
     };
+    match args.command {
+        Command::Check => {
+            if !args.quiet {
+                println!("Robinson");
+            }
+        }
+        Command::Solve => write_order(args, read, &order.permut)?,
+        Command::Reorder => {
+            // Without labels, points keep the number they had in the file
+            let labels: Vec<String> = match read.labels_in_order(&order.permut) {
+                Some(labels) => labels.into_iter().map(str::to_string).collect(),
+                None => order.permut.iter().map(u32::to_string).collect(),
+            };
+            let reordered = read.dist.permut_matrice(&order.permut);
+            write_matrice(args, &reordered, Some(&labels))?;
+        }
+        Command::Help => unreachable!("handled before reading a matrice"),
+    }
+    return Ok(ROBINSON);
+}
 
-    let shuffle_permut = get_random_permutation(SIZE);
+fn report_not_robinson(args: &Args, not_robinson: &NotRobinson) {
+    if args.command == Command::Check {
+        if !args.quiet {
+            println!("{not_robinson}");
+        }
+    } else {
+        eprintln!("robinson: {not_robinson}");
+    }
+}
 
-    let shuffled_distance_matrice = initial_distance_matrice.permut_matrice(&shuffle_permut);
+/// The input file, or the standard input kept in memory as the matrice may be read twice
+enum Input<'a> {
+    File(&'a Path),
+    Stdin(Vec<u8>),
+}
 
-    if DISPLAY_INITIAL_MAT {
-        shuffled_distance_matrice.display_mat()
+impl Input<'_> {
+    fn open(args: &Args) -> Result<Input<'_>, Box<dyn Error>> {
+        return Ok(match &args.input {
+            Some(path) => Input::File(path),
+            None => {
+                let mut bytes = Vec::new();
+                io::stdin().read_to_end(&mut bytes)?;
+                Input::Stdin(bytes)
+            }
+        });
     }
 
-    let mut robin = Robin::new(shuffled_distance_matrice.distance_mat);
+    ///Return a reader from the start of the input
+    fn reader(&self) -> Result<Box<dyn BufRead + '_>, Box<dyn Error>> {
+        return Ok(match self {
+            Input::File(path) => {
+                Box::new(BufReader::new(File::open(path).map_err(|error| {
+                    format!("can't open {}: {error}", path.display())
+                })?))
+            }
+            Input::Stdin(bytes) => Box::new(bytes.as_slice()),
+        });
+    }
+}
 
-    let time_start = SystemTime::now();
-    println!("{}", robin.resolve_robin()); //true since, by construction, the given matrice admit a compatible order
-    let time_end = SystemTime::now();
+///Read the matrice of the input in the format asked for
+fn read_matrice<T>(args: &Args, input: &Input) -> Result<LabeledMatrice<T>, Box<dyn Error>>
+where
+    T: Ord + Copy + Default + FromStr + NpyDistance,
+{
+    let reader = input.reader()?;
+    let format = args.input_format();
+    return Ok(match format {
+        Format::Csv | Format::Tsv => DistanceMatrice::read_csv(reader, &args.csv_format(format))?,
+        Format::Phylip => DistanceMatrice::read_phylip(reader, &args.phylip_format())?,
+        Format::Npy => LabeledMatrice {
+            dist: DistanceMatrice::read_npy(reader)?,
+            labels: None,
+        },
+    });
+}
 
-    let time_diff = time_end
-        .duration_since(time_start)
-        .expect("Clock may have gone backwards");
+///Return true if the matrice couldn't be read only because a distance isn't an `i64`
+fn is_not_integer(error: &(dyn Error + 'static)) -> bool {
+    return matches!(error.downcast_ref(), Some(CsvError::Parse { .. }))
+        || matches!(error.downcast_ref(), Some(PhylipError::Parse { .. }))
+        || matches!(error.downcast_ref(), Some(NpyError::Value { .. }));
+}
 
-    println!("It took {:?} for a {}x{} matrice", time_diff, SIZE, SIZE);
+///Open the output file, or the standard output
+fn output(args: &Args) -> Result<Box<dyn Write>, Box<dyn Error>> {
+    return Ok(match &args.output {
+        Some(path)
+            if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("npz")) =>
+        {
+            return Err(format!(
+                "can't write {}: .npz archives can't be written, use .npy",
+                path.display()
+            )
+            .into())
+        }
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|error| {
+                format!("can't create {}: {error}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    });
+}
 
-    if DISPLAY_FOUND_PERMUTATION {
-        println!("{:?}", robin.solved_permut.clone().unwrap());
+fn write_matrice<T: Ord + Copy + fmt::Display + NpyDistance>(
+    args: &Args,
+    dist: &DistanceMatrice<T>,
+    labels: Option<&[String]>,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = output(args)?;
+    let format = args.output_format();
+    match format {
+        Format::Csv | Format::Tsv => {
+            dist.write_csv(&mut writer, &args.csv_format(format), labels)?
+        }
+        Format::Phylip => dist.write_phylip(&mut writer, &args.phylip_format(), labels)?,
+        Format::Npy => dist.write_npy(&mut writer)?,
     }
+    writer.flush()?;
+    return Ok(());
+}
 
-    if DISPLAY_FINAL_MAT {
-        let final_matrice = robin
-            .dist
-            .permut_matrice(&robin.solved_permut.clone().unwrap());
-        final_matrice.display_mat();
-
-        println!("{}", final_matrice.is_robinson()); // Is indeed robinson
+///Write the points in the order found, by label if the file has labels. A `.npy` output file gets the points as an array of indices instead.
+fn write_order<T>(
+    args: &Args,
+    read: &LabeledMatrice<T>,
+    permut: &Permutation,
+) -> Result<(), Box<dyn Error>> {
+    if args.output_file_format() == Some(Format::Npy) {
+        if args.one_based {
+            return Err(
+                "--one-based can't be used with a .npy output, NumPy indices start at 0".into(),
+            );
+        }
+        let mut writer = output(args)?;
+        permut.write_npy(&mut writer)?;
+        writer.flush()?;
+        return Ok(());
+    }
+    let mut writer = output(args)?;
+    if let Some(labels) = read.labels_in_order(permut) {
+        for label in labels {
+            writeln!(writer, "{label}")?;
+        }
+    } else {
+        let first = if args.one_based { 1 } else { 0 };
+        for &point in permut {
+            writeln!(writer, "{}", point + first)?;
+        }
     }
+    writer.flush()?;
+    return Ok(());
+}
+
+#[test]
+fn test_solve_to_npy() {
+    let dir = std::env::temp_dir().join(format!("robinson-solve-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("dist.csv");
+    std::fs::write(&input, "0,3,1\n3,0,2\n1,2,0\n").unwrap();
+    let solve = |options: &str, output: &str| {
+        let line = format!(
+            "solve {options} {} -o {}",
+            input.display(),
+            dir.join(output).display()
+        );
+        run(&cli::parse(line.split_whitespace().map(str::to_string)).unwrap())
+    };
+
+    assert_eq!(solve("", "order.npy").unwrap(), ROBINSON);
+    let written = std::fs::read(dir.join("order.npy")).unwrap();
+    assert!(written.starts_with(b"\x93NUMPY"));
+    let points: Vec<i64> = written[written.len() - 3 * 8..]
+        .chunks_exact(8)
+        .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    assert!(points == vec![0, 2, 1] || points == vec![1, 2, 0]);
+
+    assert!(solve("", "order.npz").is_err());
+    assert!(!dir.join("order.npz").exists());
+    assert!(solve("--one-based", "order.npy").is_err());
+    assert_eq!(solve("--one-based", "order.txt").unwrap(), ROBINSON);
+    let written = std::fs::read_to_string(dir.join("order.txt")).unwrap();
+    assert!(written == "1\n3\n2\n" || written == "2\n3\n1\n");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_read_integers_exactly() {
+    let dir = std::env::temp_dir().join(format!("robinson-integers-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("dist.csv"), dir.join("out.csv"));
+    let run_on = |options: &str, text: &str| {
+        std::fs::write(&input, text).unwrap();
+        let line = format!("{options} {} -o {}", input.display(), output.display());
+        run(&cli::parse(line.split_whitespace().map(str::to_string)).unwrap()).unwrap()
+    };
+
+    // 2^53 + 1 and 2^53 are the same f64
+    let code = run_on(
+        "reorder",
+        "0,9007199254740993,9007199254740992\n9007199254740993,0,1\n9007199254740992,1,0\n",
+    );
+    assert_eq!(code, ROBINSON);
+    let reordered = std::fs::read_to_string(&output).unwrap();
+    assert!(reordered.contains("9007199254740993") && reordered.contains("9007199254740992"));
+
+    // Other numbers are read as floats
+    assert_eq!(
+        run_on("check -q", "0,0.5,2\n0.5,0,1.5\n2,1.5,0\n"),
+        ROBINSON
+    );
+    assert_eq!(
+        run_on("check -q --in-order", "0,2,0.5\n2,0,1.5\n0.5,1.5,0\n"),
+        NOT_ROBINSON
+    );
+
+    // d(0, 2) is 1 less than d(0, 1), integers are within a tolerance of 1.5 if they are within 1
+    let off_by_one = "0,2,1\n2,0,1\n1,1,0\n";
+    assert_eq!(
+        run_on("check -q --in-order -t 0.9", off_by_one),
+        NOT_ROBINSON
+    );
+    assert_eq!(run_on("check -q --in-order -t 1.5", off_by_one), ROBINSON);
+
+    // Only a distance that isn't an integer makes the file read again as floats
+    std::fs::write(&input, "0,1,2\n1,0,1\n3,1,0\n").unwrap();
+    let line = format!("check {}", input.display());
+    let error = run(&cli::parse(line.split_whitespace().map(str::to_string)).unwrap()).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(CsvError::Matrice(
+            robinson_mmodules::MatriceError::Asymmetric { .. }
+        ))
+    ));
+    std::fs::remove_dir_all(dir).unwrap();
 }
@@ -0,0 +1,101 @@
+use super::rectangular::is_unimodal;
+use super::{Dissimilarity, DistanceMatrice, Permutation, PqTree, Robin};
+
+/// Return true if, going around the circle of `n` points from any of them, distances grow then decrease
+fn is_circular_unimodal<T: Ord + Copy>(n: u32, d: impl Fn(u32, u32) -> T) -> bool {
+    return (0..n).all(|x| is_unimodal((1..n).map(|k| d(x, (x + k) % n))));
+}
+
+impl<T: Ord + Copy> DistanceMatrice<T> {
+    ///Return true if the matrice is circular Robinson along `permut`, read as a circular order, false otherwise
+    ///
+    /// Going around the circle from any point, in either direction, distances grow until the farthest points then decrease : every ball is an arc of the circle. Robinson orders are circular Robinson ones too.
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if `permut` doesn't have as many points as the matrice
+    pub fn is_circular_robinson(&self, permut: &Permutation) -> bool {
+        let view = self.permuted(permut);
+        return is_circular_unimodal(view.len() as u32, |i, j| view.d(i, j));
+    }
+}
+
+impl<T: Ord + Copy, D: Dissimilarity<Distance = T>> Robin<T, D> {
+    /// Tries to find a circular order of the points along which the distance matrice is circular Robinson, see `DistanceMatrice::is_circular_robinson`.
+    ///
+    /// Return the order, to be read circularly, or `None` if the matrice admits none. Every ball must be an arc : the balls containing the first point are replaced by their complement, the order is then one keeping every set consecutive. Balls are refined into the orders one after the other, up to n² of them, so it takes O(n³ log n).
+    ///
+    /// Distances are compared exactly, the tolerance set with `with_tolerance` isn't used.
+    pub fn resolve_circular(&self) -> Option<Permutation> {
+        let n = self.dist.len();
+        let mut tree = PqTree::from_intervals(&(0..n as u32).collect::<Vec<u32>>(), vec![]);
+        let mut in_set = vec![false; n];
+        for x in 0..n as u32 {
+            let mut others: Vec<u32> = (0..n as u32).filter(|&y| y != x).collect();
+            others.sort_by_key(|&y| self.dist.d(x, y));
+            // Each ball is x and a prefix of the others, the last one is every point
+            for k in 1..others.len() {
+                if self.dist.d(x, others[k - 1]) == self.dist.d(x, others[k]) {
+                    continue;
+                }
+                in_set.fill(false);
+                in_set[x as usize] = true;
+                for &y in &others[..k] {
+                    in_set[y as usize] = true;
+                }
+                if in_set[0] {
+                    for point in in_set.iter_mut() {
+                        *point = !*point;
+                    }
+                }
+                tree = tree.reduce(&in_set)?;
+            }
+        }
+        return Some(tree.frontier());
+    }
+}
+
+#[test]
+fn test_circular_robinson() {
+    // 6 points evenly spread on a circle
+    let rows: Vec<Vec<u32>> = (0..6)
+        .map(|i: u32| {
+            (0..6)
+                .map(|j: u32| i.abs_diff(j).min(6 - i.abs_diff(j)))
+                .collect()
+        })
+        .collect();
+    let circle = DistanceMatrice::try_from(rows).unwrap();
+    let identity = Permutation::try_from(vec![0, 1, 2, 3, 4, 5]).unwrap();
+    assert!(circle.is_circular_robinson(&identity));
+    assert!(!circle.is_robinson());
+    assert!(circle.is_circular_robinson(&Permutation::try_from(vec![3, 4, 5, 0, 1, 2]).unwrap()));
+
+    let shuffled = circle.permut_matrice(&Permutation::try_from(vec![4, 0, 2, 5, 1, 3]).unwrap());
+    assert!(!shuffled.is_circular_robinson(&identity));
+    let robin = Robin::from(&shuffled);
+    assert!(robin.solve().is_err());
+    let order = robin.resolve_circular().unwrap();
+    assert!(shuffled.is_circular_robinson(&order));
+
+    // Robinson matrices are circular Robinson
+    let line = DistanceMatrice::try_from(vec![
+        vec![0, 3, 1, 4],
+        vec![3, 0, 2, 1],
+        vec![1, 2, 0, 3],
+        vec![4, 1, 3, 0],
+    ])
+    .unwrap();
+    let order = Robin::from(&line).resolve_circular().unwrap();
+    assert!(line.is_circular_robinson(&order));
+
+    let tangled = DistanceMatrice::try_from(vec![
+        vec![0, 1, 3, 1, 2],
+        vec![1, 0, 1, 2, 2],
+        vec![3, 1, 0, 2, 3],
+        vec![1, 2, 2, 0, 2],
+        vec![2, 2, 3, 2, 0],
+    ])
+    .unwrap();
+    assert_eq!(Robin::from(&tangled).resolve_circular(), None);
+}
@@ -0,0 +1,63 @@
+use super::rectangular::unimodal_order;
+use super::{Permutation, RectangularMatrice};
+
+///Return an order of the columns of a 0/1 matrice putting the ones of every row next to each other, `None` if the matrice doesn't have the consecutive-ones property
+///
+/// Along such an order, the number of rows holding two columns is a Robinson similarity, its compatible orders are found with `Robin` and then restricted to the ones keeping the columns of every row consecutive. The similarity being Robinson isn't enough : in rows `{0, 1}`, `{1, 2}` and `{0, 2}` every pair of columns shares one row, but no order keeps all three rows consecutive.
+///
+/// Transpose the matrice to order its rows instead.
+pub fn consecutive_ones_order(matrix: &RectangularMatrice<bool>) -> Option<Permutation> {
+    return unimodal_order(matrix.n_columns(), matrix.n_rows(), |j, k| matrix.get(k, j));
+}
+
+#[test]
+fn test_consecutive_ones_order() {
+    let matrix = |rows: &[&[u32]], columns: u32| -> RectangularMatrice<bool> {
+        let rows: Vec<Vec<bool>> = rows
+            .iter()
+            .map(|row| (0..columns).map(|j| row.contains(&j)).collect())
+            .collect();
+        return RectangularMatrice::try_from(rows).unwrap();
+    };
+    let keeps_rows_consecutive = |matrix: &RectangularMatrice<bool>, order: &Permutation| {
+        return (0..matrix.n_rows()).all(|i| {
+            let ones: Vec<usize> = (0..order.len())
+                .filter(|&k| matrix.get(i, order[k] as usize))
+                .collect();
+            return ones.is_empty() || ones[ones.len() - 1] - ones[0] + 1 == ones.len();
+        });
+    };
+
+    // Intervals of 0..6, with the columns shuffled
+    let intervals = matrix(&[&[0, 1, 2], &[2, 3], &[3, 4, 5], &[1, 2, 3, 4]], 6);
+    let shuffled = intervals.reordered(&super::Seriation {
+        rows: Permutation::identity(4),
+        columns: Permutation::try_from(vec![4, 1, 5, 0, 3, 2]).unwrap(),
+    });
+    assert!(!keeps_rows_consecutive(
+        &shuffled,
+        &Permutation::identity(6)
+    ));
+    let order = consecutive_ones_order(&shuffled).unwrap();
+    assert!(keeps_rows_consecutive(&shuffled, &order));
+    let nested = matrix(&[&[0, 1], &[1, 2], &[2, 3], &[0, 1, 2, 3, 4]], 5);
+    assert!(consecutive_ones_order(&nested).is_some());
+
+    // Matrices without the property, although every pair of columns shares a row in the first one
+    assert_eq!(
+        consecutive_ones_order(&matrix(&[&[0, 1], &[1, 2], &[0, 2]], 3)),
+        None
+    );
+    assert_eq!(
+        consecutive_ones_order(&matrix(&[&[0, 1], &[1, 2], &[2, 3], &[3, 0]], 4)),
+        None
+    );
+    assert_eq!(
+        consecutive_ones_order(&matrix(&[&[0, 1], &[0, 2], &[0, 3]], 4)),
+        None
+    );
+    assert_eq!(
+        consecutive_ones_order(&matrix(&[&[1, 2, 3], &[0, 1], &[3, 4], &[1, 3, 5]], 6)),
+        None
+    );
+}
@@ -0,0 +1,501 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use super::{Dissimilarity, DistanceMatrice, LabeledMatrice, MatriceError};
+
+/// Which part of the matrice a CSV file holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Triangle {
+    /// Every line holds all `n` distances
+    Full,
+    /// Line `i` holds `d(i, i), ..., d(i, n - 1)`, with or without `i` empty fields before them
+    Upper,
+    /// Line `i` holds `d(i, 0), ..., d(i, i)`, with or without `n - i - 1` empty fields after them
+    Lower,
+}
+
+/// How a dissimilarity matrice is laid out in a CSV (or TSV) file, see `DistanceMatrice::read_csv` and `write_csv`.
+///
+/// Fields can be quoted with `"`, a `""` in a quoted field being a single `"`. Spaces around fields and empty lines are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvFormat {
+    pub delimiter: char,
+    /// The first line holds the labels of the points
+    pub header: bool,
+    /// The first field of each line is the label of its point
+    pub row_labels: bool,
+    pub triangle: Triangle,
+    /// The file holds similarities, read with `Similarity` : its diagonal isn't checked
+    pub similarities: bool,
+}
+
+impl CsvFormat {
+    ///Comma separated full matrice, without labels
+    pub fn csv() -> CsvFormat {
+        return CsvFormat {
+            delimiter: ',',
+            header: false,
+            row_labels: false,
+            triangle: Triangle::Full,
+            similarities: false,
+        };
+    }
+
+    ///Tab separated full matrice, without labels
+    pub fn tsv() -> CsvFormat {
+        return CsvFormat {
+            delimiter: '\t',
+            ..CsvFormat::csv()
+        };
+    }
+
+    pub fn with_header(mut self, header: bool) -> CsvFormat {
+        self.header = header;
+        return self;
+    }
+
+    pub fn with_row_labels(mut self, row_labels: bool) -> CsvFormat {
+        self.row_labels = row_labels;
+        return self;
+    }
+
+    pub fn with_triangle(mut self, triangle: Triangle) -> CsvFormat {
+        self.triangle = triangle;
+        return self;
+    }
+
+    pub fn with_similarities(mut self, similarities: bool) -> CsvFormat {
+        self.similarities = similarities;
+        return self;
+    }
+}
+
+/// Why a CSV file couldn't be read as a `DistanceMatrice`. Lines are numbered from 1, as in a text editor.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// Field `field` (numbered from 1) of line `line` isn't a distance
+    Parse {
+        line: usize,
+        field: usize,
+        value: String,
+    },
+    /// Line `line` has `len` distances instead of `expected`
+    FieldCount {
+        line: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A quoted field of line `line` isn't closed
+    UnclosedQuote {
+        line: usize,
+    },
+    /// The row label of line `line` differs from the header label of the same point
+    LabelMismatch {
+        line: usize,
+        label: String,
+        expected: String,
+    },
+    /// The file has `len` lines of distances instead of `expected`
+    LineCount {
+        len: usize,
+        expected: usize,
+    },
+    /// The distances read don't form a valid matrice (empty, asymmetric, ...)
+    Matrice(MatriceError),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "can't read the file : {error}"),
+            CsvError::Parse { line, field, value } => {
+                write!(f, "line {line}, field {field} : {value:?} isn't a distance")
+            }
+            CsvError::FieldCount {
+                line,
+                len,
+                expected,
+            } => write!(f, "line {line} has {len} distances instead of {expected}"),
+            CsvError::UnclosedQuote { line } => {
+                write!(f, "line {line} has a quoted field which isn't closed")
+            }
+            CsvError::LabelMismatch {
+                line,
+                label,
+                expected,
+            } => write!(
+                f,
+                "line {line} is labeled {label:?} but the header labels this point {expected:?}"
+            ),
+            CsvError::LineCount { len, expected } => write!(
+                f,
+                "the file has {len} lines of distances instead of {expected}"
+            ),
+            CsvError::Matrice(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            CsvError::Io(error) => Some(error),
+            CsvError::Matrice(error) => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> CsvError {
+        return CsvError::Io(error);
+    }
+}
+
+impl From<MatriceError> for CsvError {
+    fn from(error: MatriceError) -> CsvError {
+        return CsvError::Matrice(error);
+    }
+}
+
+/// A line of distances, with its line number and row label
+struct Record {
+    line: usize,
+    label: Option<String>,
+    fields: Vec<String>,
+}
+
+/// Split a line into trimmed fields, unquoting quoted ones
+fn split_fields(text: &str, delimiter: char, line: usize) -> Result<Vec<String>, CsvError> {
+    let mut fields = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        let mut field = String::new();
+        while chars
+            .peek()
+            .is_some_and(|&c| c != delimiter && c.is_whitespace())
+        {
+            chars.next();
+        }
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    None => return Err(CsvError::UnclosedQuote { line }),
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                }
+            }
+            while chars.peek().is_some_and(|&c| c != delimiter) {
+                chars.next();
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == delimiter {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+            }
+            field = field.trim().to_string();
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Quote a label if it couldn't be read back otherwise
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter)
+        || field.contains('"')
+        || field.trim() != field
+        || field.contains('\n')
+    {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return field.to_string();
+}
+
+impl<T: Ord + Copy + Default + FromStr> DistanceMatrice<T> {
+    ///Read a dissimilarity matrice from a CSV (or TSV) file laid out as `format` tells
+    ///
+    /// Full matrices are checked as `new_checked` does, so their two triangles must be equal, or as `new_checked_similarities` does if the format holds similarities. If the file has both a header and row labels, the header may start with an empty corner field and the labels must match.
+    pub fn read_csv(
+        reader: impl BufRead,
+        format: &CsvFormat,
+    ) -> Result<LabeledMatrice<T>, CsvError> {
+        let mut header: Option<Vec<String>> = None;
+        let mut records = Vec::new();
+        for (index, text) in reader.lines().enumerate() {
+            let text = text?;
+            let line = index + 1;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let mut fields = split_fields(&text, format.delimiter, line)?;
+            if format.header && header.is_none() {
+                header = Some(fields);
+                continue;
+            }
+            let label = if format.row_labels {
+                Some(fields.remove(0))
+            } else {
+                None
+            };
+            records.push(Record {
+                line,
+                label,
+                fields,
+            });
+        }
+
+        let n = match (&mut header, format.triangle) {
+            (Some(header), _) => {
+                if format.row_labels && header.len() == records.len() + 1 {
+                    header.remove(0);
+                }
+                header.len()
+            }
+            (None, Triangle::Lower) => records.len(),
+            (None, _) => records.first().map_or(0, |record| record.fields.len()),
+        };
+        if records.len() != n {
+            return Err(CsvError::LineCount {
+                len: records.len(),
+                expected: n,
+            });
+        }
+
+        let mut distance_mat = vec![vec![T::default(); n]; n];
+        for (i, record) in records.iter().enumerate() {
+            if let (Some(label), Some(header)) = (&record.label, &header) {
+                if *label != header[i] {
+                    return Err(CsvError::LabelMismatch {
+                        line: record.line,
+                        label: label.clone(),
+                        expected: header[i].clone(),
+                    });
+                }
+            }
+            let (first, expected) = match format.triangle {
+                Triangle::Full => (0, n),
+                Triangle::Upper => (i, n - i),
+                Triangle::Lower => (0, i + 1),
+            };
+            let mut fields: &[String] = &record.fields;
+            // Number of fields before the distances, to report the right field on errors
+            let mut skipped = usize::from(format.row_labels);
+            if fields.len() == n && expected < n {
+                // Padded with empty fields in the missing triangle
+                let padding = if format.triangle == Triangle::Upper {
+                    &fields[..i]
+                } else {
+                    &fields[i + 1..]
+                };
+                if padding.iter().all(|field| field.is_empty()) {
+                    fields = &fields[first..first + expected];
+                    skipped += first;
+                }
+            }
+            if fields.len() != expected {
+                return Err(CsvError::FieldCount {
+                    line: record.line,
+                    len: fields.len(),
+                    expected,
+                });
+            }
+            for (k, field) in fields.iter().enumerate() {
+                let d = field.parse().map_err(|_| CsvError::Parse {
+                    line: record.line,
+                    field: skipped + k + 1,
+                    value: field.clone(),
+                })?;
+                let j = first + k;
+                if format.triangle == Triangle::Lower {
+                    distance_mat[j][i] = d;
+                } else {
+                    distance_mat[i][j] = d;
+                }
+            }
+        }
+
+        let labels = match header {
+            Some(header) => Some(header),
+            None => records
+                .into_iter()
+                .map(|record| record.label)
+                .collect::<Option<Vec<String>>>(),
+        };
+        let dist = if format.similarities {
+            DistanceMatrice::new_checked_similarities(distance_mat)?
+        } else {
+            DistanceMatrice::new_checked(distance_mat)?
+        };
+        return Ok(LabeledMatrice { dist, labels });
+    }
+}
+
+impl<T: Ord + Copy + fmt::Display> DistanceMatrice<T> {
+    ///Write the matrice to a CSV (or TSV) file laid out as `format` tells, see `read_csv`
+    ///
+    /// If `format` has a header or row labels, they are taken from `labels`, points being numbered from 0 if there are none.
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if `labels` doesn't have as many labels as the matrice has points
+    pub fn write_csv(
+        &self,
+        mut writer: impl Write,
+        format: &CsvFormat,
+        labels: Option<&[String]>,
+    ) -> io::Result<()> {
+        let n = self.len();
+        if let Some(labels) = labels {
+            assert_eq!(labels.len(), n, "labels and distance matrice sizes differ");
+        }
+        let delimiter = format.delimiter.to_string();
+        let label = |i: usize| match labels {
+            Some(labels) => quote_field(&labels[i], format.delimiter),
+            None => i.to_string(),
+        };
+
+        if format.header {
+            let mut fields: Vec<String> = (0..n).map(label).collect();
+            if format.row_labels {
+                fields.insert(0, String::new());
+            }
+            writeln!(writer, "{}", fields.join(&delimiter))?;
+        }
+        for i in 0..n {
+            let columns = match format.triangle {
+                Triangle::Full => 0..n,
+                Triangle::Upper => i..n,
+                Triangle::Lower => 0..i + 1,
+            };
+            let mut fields: Vec<String> = columns
+                .map(|j| self.d(i as u32, j as u32).to_string())
+                .collect();
+            if format.row_labels {
+                fields.insert(0, label(i));
+            }
+            writeln!(writer, "{}", fields.join(&delimiter))?;
+        }
+        return Ok(());
+    }
+}
+
+#[test]
+fn test_split_fields() {
+    assert_eq!(
+        split_fields(" a , \"b,\"\"c\"\" \" ,,d", ',', 1).unwrap(),
+        vec!["a", "b,\"c\" ", "", "d"]
+    );
+    assert_eq!(split_fields("1\t2", '\t', 1).unwrap(), vec!["1", "2"]);
+    assert!(matches!(
+        split_fields("\"a", ',', 4),
+        Err(CsvError::UnclosedQuote { line: 4 })
+    ));
+    assert_eq!(quote_field("a,b", ','), "\"a,b\"");
+    assert_eq!(quote_field("a b", ','), "a b");
+}
+
+#[test]
+fn test_read_write_csv() {
+    let rows = vec![
+        vec![0, 1, 3, 4],
+        vec![1, 0, 2, 3],
+        vec![3, 2, 0, 1],
+        vec![4, 3, 1, 0],
+    ];
+    let upper = vec![
+        vec![0, 1, 3, 4],
+        vec![0, 0, 2, 3],
+        vec![0, 0, 0, 1],
+        vec![0, 0, 0, 0],
+    ];
+    let labels: Vec<String> = ["a", "b,c", "d", "e"].map(String::from).to_vec();
+
+    let full = "\"\",a,\"b,c\",d,e\na,0,1,3,4\n\"b,c\",1,0,2,3\nd,3,2,0,1\n\ne,4,3,1,0\n";
+    let format = CsvFormat::csv().with_header(true).with_row_labels(true);
+    let read = DistanceMatrice::<u32>::read_csv(full.as_bytes(), &format).unwrap();
+    assert_eq!(read.dist.to_rows(), rows);
+    assert_eq!(read.labels.as_ref(), Some(&labels));
+
+    let padded_upper = "0,1,3,4\n,0,2,3\n,,0,1\n , , ,0\n";
+    let ragged_upper = "0,1,3,4\n0,2,3\n0,1\n0\n";
+    let format = CsvFormat::csv().with_triangle(Triangle::Upper);
+    for text in [padded_upper, ragged_upper] {
+        let read = DistanceMatrice::<u32>::read_csv(text.as_bytes(), &format).unwrap();
+        assert_eq!(read.dist.to_rows(), upper);
+        assert_eq!(read.labels, None);
+    }
+
+    let lower = "a\t0\nb\t1\t0\nc\t3\t2\t0\nd\t4\t3\t1\t0\n";
+    let format = CsvFormat::tsv()
+        .with_row_labels(true)
+        .with_triangle(Triangle::Lower);
+    let read = DistanceMatrice::<u32>::read_csv(lower.as_bytes(), &format).unwrap();
+    assert_eq!(read.dist.to_rows(), upper);
+    let mut written = Vec::new();
+    read.dist
+        .write_csv(&mut written, &format, read.labels.as_deref())
+        .unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), lower);
+
+    // Round trip through every layout
+    let dist = DistanceMatrice::try_from(upper.clone()).unwrap();
+    for triangle in [Triangle::Full, Triangle::Upper, Triangle::Lower] {
+        let format = CsvFormat::csv()
+            .with_header(true)
+            .with_row_labels(true)
+            .with_triangle(triangle);
+        let mut written = Vec::new();
+        dist.write_csv(&mut written, &format, Some(&labels))
+            .unwrap();
+        let read = DistanceMatrice::<u32>::read_csv(written.as_slice(), &format).unwrap();
+        assert_eq!(read.dist.to_condensed(), dist.to_condensed());
+        assert_eq!(read.labels.as_ref(), Some(&labels));
+    }
+
+    let read = |text: &str, format: CsvFormat| {
+        return DistanceMatrice::<u32>::read_csv(text.as_bytes(), &format).err();
+    };
+    assert!(matches!(
+        read("0,1\n1,x\n", CsvFormat::csv()),
+        Some(CsvError::Parse { line: 2, field: 2, ref value }) if value == "x"
+    ));
+    assert!(matches!(
+        read("0,1,2\n1,0\n2,1,0\n", CsvFormat::csv()),
+        Some(CsvError::FieldCount {
+            line: 2,
+            len: 2,
+            expected: 3
+        })
+    ));
+    assert!(matches!(
+        read(
+            "a,b\na,0,1\nc,1,0\n",
+            CsvFormat::csv().with_header(true).with_row_labels(true)
+        ),
+        Some(CsvError::LabelMismatch { line: 3, .. })
+    ));
+    assert!(matches!(
+        read("0,1\n1,0\n0,0\n", CsvFormat::csv()),
+        Some(CsvError::LineCount {
+            len: 3,
+            expected: 2
+        })
+    ));
+    assert!(matches!(
+        read("0,1\n2,0\n", CsvFormat::csv()),
+        Some(CsvError::Matrice(MatriceError::Asymmetric { i: 0, j: 1 }))
+    ));
+}
@@ -0,0 +1,32 @@
+/// A source of distances between `len()` points numbered from 0, which `Robin` can run on.
+///
+/// `DistanceMatrice` is one, implement it to compute distances on the fly (from feature vectors, a memory-mapped file, ...) without building a matrice first. Every distance is read many times, so `d` should be cheap.
+///
+/// `d` must be symmetric, `d(i, j) == d(j, i)`, and is only called with `i` and `j` less than `len()`. `d(i, i)` should be the least distance, usually zero.
+pub trait Dissimilarity {
+    /// Type of the distances, any type with a total order. Floating point distances can be wrapped in `OrdFloat`.
+    type Distance: Ord + Copy;
+
+    ///Return the number of points
+    fn len(&self) -> usize;
+
+    ///Return the distance between points `i` and `j`
+    fn d(&self, i: u32, j: u32) -> Self::Distance;
+
+    fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+}
+
+/// So `Robin` can run on a borrowed matrice
+impl<D: Dissimilarity + ?Sized> Dissimilarity for &D {
+    type Distance = D::Distance;
+
+    fn len(&self) -> usize {
+        return (**self).len();
+    }
+
+    fn d(&self, i: u32, j: u32) -> D::Distance {
+        return (**self).d(i, j);
+    }
+}
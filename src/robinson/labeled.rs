@@ -0,0 +1,25 @@
+use super::{DistanceMatrice, Permutation};
+
+/// A `DistanceMatrice` read from a file, along with the labels of its points if the file has any
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LabeledMatrice<T = u32> {
+    pub dist: DistanceMatrice<T>,
+    /// Label of each point, from a CSV header or row labels, or PHYLIP taxon names
+    pub labels: Option<Vec<String>>,
+}
+
+impl<T> LabeledMatrice<T> {
+    ///Return the labels of the points in the order of `permut`, `None` if there are no labels
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if `permut` has points the matrice doesn't have
+    pub fn labels_in_order(&self, permut: &Permutation) -> Option<Vec<&str>> {
+        return self.labels.as_ref().map(|labels| {
+            permut
+                .iter()
+                .map(|&x| labels[x as usize].as_str())
+                .collect()
+        });
+    }
+}
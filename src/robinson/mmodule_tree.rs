@@ -0,0 +1,115 @@
+use std::fmt;
+
+/// The decomposition of the points built by `Robin::mmodule_tree`.
+///
+/// A node holds a set of points, the first one being its `pivot`. The other points are partitioned into the copoints of the pivot, which are mmodules and the children of the node. A node with a single point is a leaf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MmoduleTree {
+    pub pivot: u32,
+    /// Every point of the node, in the order found for them
+    pub points: Vec<u32>,
+    pub copoints: Vec<MmoduleTree>,
+}
+
+impl MmoduleTree {
+    ///Return true if the node has no copoint (it's then made of its pivot only)
+    pub fn is_leaf(&self) -> bool {
+        return self.copoints.is_empty();
+    }
+
+    ///Return the number of levels of the tree, 1 for a leaf
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((node, node_depth)) = stack.pop() {
+            depth = depth.max(node_depth);
+            stack.extend(node.copoints.iter().map(|child| (child, node_depth + 1)));
+        }
+        return depth;
+    }
+
+    ///Iterate over the nodes of the tree, depth first, parents before their copoints
+    pub fn iter(&self) -> MmoduleTreeIter<'_> {
+        return MmoduleTreeIter { stack: vec![self] };
+    }
+}
+
+impl<'a> IntoIterator for &'a MmoduleTree {
+    type Item = &'a MmoduleTree;
+    type IntoIter = MmoduleTreeIter<'a>;
+
+    fn into_iter(self) -> MmoduleTreeIter<'a> {
+        return self.iter();
+    }
+}
+
+/// Depth first iterator over the nodes of a `MmoduleTree`, see `MmoduleTree::iter`
+pub struct MmoduleTreeIter<'a> {
+    stack: Vec<&'a MmoduleTree>,
+}
+
+impl<'a> Iterator for MmoduleTreeIter<'a> {
+    type Item = &'a MmoduleTree;
+
+    fn next(&mut self) -> Option<&'a MmoduleTree> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.copoints.iter().rev());
+        return Some(node);
+    }
+}
+
+/// One line per node, indented by its depth : the pivot then the points of the node
+impl fmt::Display for MmoduleTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(self, 0)];
+        while let Some((node, indent)) = stack.pop() {
+            writeln!(
+                f,
+                "{:indent$}{} : {:?}",
+                "",
+                node.pivot,
+                node.points,
+                indent = 2 * indent
+            )?;
+            stack.extend(node.copoints.iter().rev().map(|child| (child, indent + 1)));
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+fn leaf(point: u32) -> MmoduleTree {
+    return MmoduleTree {
+        pivot: point,
+        points: vec![point],
+        copoints: vec![],
+    };
+}
+
+#[test]
+fn test_mmodule_tree_traversal() {
+    let tree = MmoduleTree {
+        pivot: 1,
+        points: vec![2, 3, 1, 4],
+        copoints: vec![
+            MmoduleTree {
+                pivot: 2,
+                points: vec![2, 3],
+                copoints: vec![leaf(3)],
+            },
+            leaf(4),
+        ],
+    };
+
+    assert_eq!(tree.depth(), 3);
+    assert_eq!(
+        tree.iter().map(|node| node.pivot).collect::<Vec<u32>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(tree.iter().filter(|node| node.is_leaf()).count(), 2);
+    assert_eq!(
+        tree.to_string(),
+        "1 : [2, 3, 1, 4]\n  2 : [2, 3]\n    3 : [3]\n  4 : [4]\n"
+    );
+}
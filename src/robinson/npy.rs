@@ -0,0 +1,540 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use super::{Dissimilarity, DistanceMatrice, MatriceError, OrdFloat, Permutation};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Distance types which can be read from and written to `.npy` files, see `DistanceMatrice::read_npy`
+pub trait NpyDistance: Copy {
+    /// NumPy type descriptor used when writing, such as `<u4`
+    const DESCR: &'static str;
+
+    ///Convert an integer read from a file, `None` if it doesn't fit
+    fn from_int(value: i128) -> Option<Self>;
+
+    ///Convert a float read from a file, `None` if it can't be a distance of this type
+    fn from_float(value: f64) -> Option<Self>;
+
+    ///Append the little endian bytes of the distance
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>);
+}
+
+macro_rules! impl_npy_int {
+    ($($int:ty => $descr:expr),*) => {
+        $(
+            impl NpyDistance for $int {
+                const DESCR: &'static str = $descr;
+
+                fn from_int(value: i128) -> Option<Self> {
+                    return <$int>::try_from(value).ok();
+                }
+
+                /// Integral floats only
+                fn from_float(value: f64) -> Option<Self> {
+                    if value.fract() != 0.0 {
+                        return None;
+                    }
+                    return <$int>::try_from(value as i128).ok();
+                }
+
+                fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_int!(
+    u8 => "|u1", u16 => "<u2", u32 => "<u4", u64 => "<u8",
+    i8 => "|i1", i16 => "<i2", i32 => "<i4", i64 => "<i8"
+);
+
+macro_rules! impl_npy_float {
+    ($($float:ty => $descr:expr),*) => {
+        $(
+            impl NpyDistance for OrdFloat<$float> {
+                const DESCR: &'static str = $descr;
+
+                fn from_int(value: i128) -> Option<Self> {
+                    return Some(OrdFloat(value as $float));
+                }
+
+                fn from_float(value: f64) -> Option<Self> {
+                    return Some(OrdFloat(value as $float));
+                }
+
+                fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.0.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_float!(f32 => "<f4", f64 => "<f8");
+
+/// Why a `.npy` (or `.npz`) file couldn't be read as a `DistanceMatrice`
+#[derive(Debug)]
+pub enum NpyError {
+    Io(io::Error),
+    /// The file doesn't start as a `.npy` file, or isn't a `.npz` archive
+    NotNpy,
+    /// The header of the array can't be read
+    Header(String),
+    /// The array holds values of type `descr`, which aren't integers nor floats
+    Dtype {
+        descr: String,
+    },
+    /// The array isn't a square matrice nor a condensed vector
+    Shape {
+        shape: Vec<usize>,
+    },
+    /// Value number `index` (in memory order) can't be a distance of the requested type
+    Value {
+        index: usize,
+    },
+    /// The `.npz` array `name` is compressed (`numpy.savez_compressed`), only `numpy.savez` archives can be read
+    Compressed {
+        name: String,
+    },
+    /// The `.npz` archive has no array `name`
+    MissingArray {
+        name: String,
+    },
+    /// The values read don't form a valid matrice (empty, asymmetric, ...)
+    Matrice(MatriceError),
+}
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NpyError::Io(error) => write!(f, "can't read the file : {error}"),
+            NpyError::NotNpy => write!(f, "the file isn't a NumPy array"),
+            NpyError::Header(header) => write!(f, "can't read the array header {header:?}"),
+            NpyError::Dtype { descr } => {
+                write!(
+                    f,
+                    "arrays of {descr:?} can't be distances, only integers and floats"
+                )
+            }
+            NpyError::Shape { shape } => write!(
+                f,
+                "an array of shape {shape:?} is neither a square matrice nor a condensed vector"
+            ),
+            NpyError::Value { index } => write!(
+                f,
+                "value {index} of the array can't be converted to the distance type"
+            ),
+            NpyError::Compressed { name } => write!(
+                f,
+                "array {name:?} is compressed, save it with numpy.savez instead of savez_compressed"
+            ),
+            NpyError::MissingArray { name } => write!(f, "the archive has no array {name:?}"),
+            NpyError::Matrice(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for NpyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            NpyError::Io(error) => Some(error),
+            NpyError::Matrice(error) => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for NpyError {
+    fn from(error: io::Error) -> NpyError {
+        return NpyError::Io(error);
+    }
+}
+
+impl From<MatriceError> for NpyError {
+    fn from(error: MatriceError) -> NpyError {
+        return NpyError::Matrice(error);
+    }
+}
+
+/// The parts of a `.npy` header this crate needs
+struct Header {
+    /// `i`, `u` or `f`
+    kind: char,
+    size: usize,
+    big_endian: bool,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+/// Value of `key` in the Python dict literal of a header, up to the next `,` or `}` (or the closing `)` of a tuple)
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{key}'"))? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else {
+        rest.find([',', '}']).unwrap_or(rest.len())
+    };
+    return Some(rest[..end].trim());
+}
+
+fn parse_header(text: &str) -> Result<Header, NpyError> {
+    let invalid = || NpyError::Header(text.to_string());
+    let descr = header_value(text, "descr")
+        .and_then(|descr| descr.strip_prefix('\'')?.strip_suffix('\''))
+        .ok_or_else(invalid)?;
+    let fortran_order = match header_value(text, "fortran_order") {
+        Some("True") => true,
+        Some("False") => false,
+        _ => return Err(invalid()),
+    };
+    let shape = header_value(text, "shape")
+        .and_then(|shape| shape.strip_prefix('(')?.strip_suffix(')'))
+        .ok_or_else(invalid)?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse::<usize>().map_err(|_| invalid()))
+        .collect::<Result<Vec<usize>, NpyError>>()?;
+
+    let dtype_error = || NpyError::Dtype {
+        descr: descr.to_string(),
+    };
+    let mut chars = descr.chars();
+    let big_endian = match chars.next() {
+        Some('>') => true,
+        Some('<') | Some('|') => false,
+        Some('=') => cfg!(target_endian = "big"),
+        _ => return Err(dtype_error()),
+    };
+    let kind = chars.next().ok_or_else(dtype_error)?;
+    let size: usize = chars.as_str().parse().map_err(|_| dtype_error())?;
+    let supported = match kind {
+        'i' | 'u' => [1, 2, 4, 8].contains(&size),
+        'f' => [4, 8].contains(&size),
+        _ => false,
+    };
+    if !supported {
+        return Err(dtype_error());
+    }
+    return Ok(Header {
+        kind,
+        size,
+        big_endian,
+        fortran_order,
+        shape,
+    });
+}
+
+/// Read the magic string and header of a `.npy` file
+fn read_header(reader: &mut impl Read) -> Result<Header, NpyError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic[..6] != MAGIC {
+        return Err(NpyError::NotNpy);
+    }
+    let header_len = if magic[6] == 1 {
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        u16::from_le_bytes(len) as usize
+    } else {
+        let mut len = [0; 4];
+        reader.read_exact(&mut len)?;
+        u32::from_le_bytes(len) as usize
+    };
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+    return parse_header(&header);
+}
+
+/// Read the `len` values following the header, in memory order
+fn read_values<T: NpyDistance>(
+    reader: &mut impl Read,
+    header: &Header,
+    len: usize,
+) -> Result<Vec<T>, NpyError> {
+    // len comes from the header, values are stored as they are read rather than allocated from it
+    let mut values = Vec::new();
+    for_each_value(reader, header, len, |_, value| values.push(value))?;
+    return Ok(values);
+}
+
+/// Read the `len` values following the header, calling `f` with the index and value of each one in memory order
+fn for_each_value<T: NpyDistance>(
+    reader: &mut impl Read,
+    header: &Header,
+    len: usize,
+    mut f: impl FnMut(usize, T),
+) -> Result<(), NpyError> {
+    let mut buffer = vec![0; header.size * 4096];
+    let mut index = 0;
+    while index < len {
+        let count = (len - index).min(4096);
+        let bytes = &mut buffer[..count * header.size];
+        reader.read_exact(bytes)?;
+        for chunk in bytes.chunks_exact(header.size) {
+            let mut raw = [0; 8];
+            if header.big_endian {
+                raw[8 - header.size..].copy_from_slice(chunk);
+                raw.reverse();
+            } else {
+                raw[..header.size].copy_from_slice(chunk);
+            }
+            let bits = u64::from_le_bytes(raw);
+            let shift = 64 - 8 * header.size as u32;
+            let value = match (header.kind, header.size) {
+                ('u', _) => T::from_int(bits as i128),
+                // Sign extend
+                ('i', _) => T::from_int((((bits << shift) as i64) >> shift) as i128),
+                ('f', 4) => T::from_float(f32::from_bits(bits as u32) as f64),
+                _ => T::from_float(f64::from_bits(bits)),
+            };
+            f(index, value.ok_or(NpyError::Value { index })?);
+            index += 1;
+        }
+    }
+    return Ok(());
+}
+
+/// Write the header of an array of `descr` values of the given shape
+fn write_header(writer: &mut impl Write, descr: &str, shape: &[usize]) -> io::Result<()> {
+    let shape = match shape {
+        [len] => format!("({len},)"),
+        _ => format!(
+            "({})",
+            shape
+                .iter()
+                .map(|dim| dim.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    // The data starts on a multiple of 64 bytes, the header ends with a newline
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    return Ok(());
+}
+
+impl<T: Ord + Copy + Default + NpyDistance> DistanceMatrice<T> {
+    ///Read a dissimilarity matrice from a `.npy` file, as written by `numpy.save`
+    ///
+    /// The array is either a square matrice, checked as `new_checked` does, or a condensed vector (see `from_condensed`) as returned by `scipy.spatial.distance.pdist`. Integer and float arrays are converted to `T` if their values fit.
+    pub fn read_npy(reader: impl Read) -> Result<DistanceMatrice<T>, NpyError> {
+        return DistanceMatrice::read_npy_checked(reader, false);
+    }
+
+    ///Read a similarity matrice from a `.npy` file, as `read_npy` does but checking a square matrice as `new_checked_similarities` does
+    pub fn read_npy_similarities(reader: impl Read) -> Result<DistanceMatrice<T>, NpyError> {
+        return DistanceMatrice::read_npy_checked(reader, true);
+    }
+
+    fn read_npy_checked(
+        mut reader: impl Read,
+        similarities: bool,
+    ) -> Result<DistanceMatrice<T>, NpyError> {
+        let header = read_header(&mut reader)?;
+        match header.shape[..] {
+            [len] => Ok(DistanceMatrice::from_condensed(read_values(
+                &mut reader,
+                &header,
+                len,
+            )?)?),
+            [rows, columns] if rows == columns => {
+                let n = rows;
+                let len = n.checked_mul(n).ok_or_else(|| NpyError::Shape {
+                    shape: header.shape.clone(),
+                })?;
+                // Rows are built as the values are read, a Fortran order array holding its columns one after the other
+                let mut distance_mat: Vec<Vec<T>> = Vec::new();
+                for_each_value(&mut reader, &header, len, |index, value| {
+                    let i = if header.fortran_order {
+                        index % n
+                    } else {
+                        index / n
+                    };
+                    if i == distance_mat.len() {
+                        distance_mat.push(Vec::new());
+                    }
+                    distance_mat[i].push(value);
+                })?;
+                if similarities {
+                    Ok(DistanceMatrice::new_checked_similarities(distance_mat)?)
+                } else {
+                    Ok(DistanceMatrice::new_checked(distance_mat)?)
+                }
+            }
+            _ => Err(NpyError::Shape {
+                shape: header.shape,
+            }),
+        }
+    }
+}
+
+impl<T: Ord + Copy + NpyDistance> DistanceMatrice<T> {
+    ///Write the matrice to a `.npy` file as a square matrice, both triangles holding the distances
+    ///
+    /// Wrap `writer` in a `BufWriter` when writing to a file.
+    pub fn write_npy(&self, mut writer: impl Write) -> io::Result<()> {
+        let n = self.len();
+        write_header(&mut writer, T::DESCR, &[n, n])?;
+        let mut bytes = Vec::new();
+        for i in 0..n as u32 {
+            bytes.clear();
+            for j in 0..n as u32 {
+                self.d(i, j).extend_le_bytes(&mut bytes);
+            }
+            writer.write_all(&bytes)?;
+        }
+        return Ok(());
+    }
+
+    ///Write the matrice to a `.npy` file as a condensed vector, see `from_condensed`
+    pub fn write_npy_condensed(&self, mut writer: impl Write) -> io::Result<()> {
+        let n = self.len();
+        write_header(&mut writer, T::DESCR, &[n * n.saturating_sub(1) / 2])?;
+        let mut bytes = Vec::new();
+        for i in 0..n as u32 {
+            bytes.clear();
+            for j in i + 1..n as u32 {
+                self.d(i, j).extend_le_bytes(&mut bytes);
+            }
+            writer.write_all(&bytes)?;
+        }
+        return Ok(());
+    }
+}
+
+impl Permutation {
+    ///Write the permutation to a `.npy` file as a vector of `int64`, so it can index NumPy arrays directly
+    pub fn write_npy(&self, mut writer: impl Write) -> io::Result<()> {
+        write_header(&mut writer, "<i8", &[self.len()])?;
+        let mut bytes = Vec::with_capacity(8 * self.len());
+        for &point in self {
+            bytes.extend_from_slice(&(point as i64).to_le_bytes());
+        }
+        writer.write_all(&bytes)?;
+        return Ok(());
+    }
+}
+
+#[test]
+fn test_parse_header() {
+    let header =
+        parse_header("{'descr': '>i2', 'fortran_order': True, 'shape': (3, 3), }").unwrap();
+    assert_eq!(
+        (
+            header.kind,
+            header.size,
+            header.big_endian,
+            header.fortran_order
+        ),
+        ('i', 2, true, true)
+    );
+    assert_eq!(header.shape, vec![3, 3]);
+    assert_eq!(
+        parse_header("{'descr': '<f8', 'fortran_order': False, 'shape': (6,), }")
+            .unwrap()
+            .shape,
+        vec![6]
+    );
+    assert!(matches!(
+        parse_header("{'descr': '<U3', 'fortran_order': False, 'shape': (6,), }"),
+        Err(NpyError::Dtype { .. })
+    ));
+}
+
+#[test]
+fn test_read_write_npy() {
+    let upper = vec![
+        vec![0, 1, 3, 4],
+        vec![0, 0, 2, 3],
+        vec![0, 0, 0, 1],
+        vec![0, 0, 0, 0],
+    ];
+    let dist = DistanceMatrice::try_from(upper).unwrap();
+
+    let mut written = Vec::new();
+    dist.write_npy(&mut written).unwrap();
+    assert_eq!((written.len() - 16 * 4) % 64, 0);
+    let read = DistanceMatrice::<u32>::read_npy(written.as_slice()).unwrap();
+    assert_eq!(read.to_condensed(), dist.to_condensed());
+    let read = DistanceMatrice::<OrdFloat<f64>>::read_npy(written.as_slice()).unwrap();
+    assert_eq!(read.to_condensed()[5], OrdFloat(1.0));
+
+    let mut written = Vec::new();
+    dist.write_npy_condensed(&mut written).unwrap();
+    let read = DistanceMatrice::<i64>::read_npy(written.as_slice()).unwrap();
+    assert!(read.is_condensed());
+    assert_eq!(read.to_condensed(), vec![1, 3, 4, 2, 3, 1]);
+
+    // Big endian fortran order floats, as numpy.save(f, a.astype('>f4'), order='F') would write them
+    let mut file = Vec::new();
+    let header = "{'descr': '>f4', 'fortran_order': True, 'shape': (2, 2), }";
+    file.extend_from_slice(MAGIC);
+    file.extend_from_slice(&[1, 0, header.len() as u8, 0]);
+    file.extend_from_slice(header.as_bytes());
+    for value in [0.0f32, 2.5, 2.5, 0.0] {
+        file.extend_from_slice(&value.to_be_bytes());
+    }
+    let read = DistanceMatrice::<OrdFloat<f32>>::read_npy(file.as_slice()).unwrap();
+    assert_eq!(read.to_condensed(), vec![OrdFloat(2.5)]);
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npy(file.as_slice()),
+        Err(NpyError::Value { index: 1 })
+    ));
+
+    let mut written = Vec::new();
+    Permutation::try_from(vec![1, 0])
+        .unwrap()
+        .write_npy(&mut written)
+        .unwrap();
+    assert_eq!(
+        &written[written.len() - 16..written.len() - 8],
+        &1i64.to_le_bytes()
+    );
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npy(written.as_slice()),
+        Err(NpyError::Matrice(MatriceError::CondensedLength { len: 2 }))
+    ));
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npy(&b"not a numpy file"[..]),
+        Err(NpyError::NotNpy)
+    ));
+}
+
+#[test]
+fn test_read_malformed_npy() {
+    let file = |shape: &str, data: &[u8]| {
+        let header = format!("{{'descr': '<u4', 'fortran_order': False, 'shape': {shape}, }}");
+        let mut file = Vec::new();
+        file.extend_from_slice(MAGIC);
+        file.extend_from_slice(&[1, 0, header.len() as u8, 0]);
+        file.extend_from_slice(header.as_bytes());
+        file.extend_from_slice(data);
+        file
+    };
+
+    // Shapes far larger than the file must fail without allocating them
+    let huge = format!("({0}, {0})", usize::MAX / 2);
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npy(file(&huge, &[0; 16]).as_slice()),
+        Err(NpyError::Shape { .. })
+    ));
+    for shape in ["(100000, 100000)", "(1000000000000,)"] {
+        assert!(matches!(
+            DistanceMatrice::<u32>::read_npy(file(shape, &[0; 16]).as_slice()),
+            Err(NpyError::Io(ref error)) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+    assert!(DistanceMatrice::<u32>::read_npy(file("(2, 2)", &[0; 16]).as_slice()).is_ok());
+}
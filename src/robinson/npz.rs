@@ -0,0 +1,291 @@
+use std::io::{self, Read, Write};
+
+use super::{DistanceMatrice, NpyDistance, NpyError, Permutation};
+
+const LOCAL_HEADER: u32 = 0x04034b50;
+const CENTRAL_HEADER: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+/// Zip files written by this crate are dated 1980-01-01
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+        *entry = c;
+    }
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = table[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    return u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    return u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+}
+
+/// Sizes of an entry stored in its zip64 extra field, in place of the 32 bits ones set to `u32::MAX`
+fn zip64_sizes(extra: &[u8], size: u32, compressed_size: u32) -> (u64, u64) {
+    let (mut size, mut compressed_size) = (size as u64, compressed_size as u64);
+    let mut at = 0;
+    while at + 4 <= extra.len() {
+        let (id, len) = (read_u16(extra, at), read_u16(extra, at + 2) as usize);
+        let data = &extra[at + 4..(at + 4 + len).min(extra.len())];
+        if id == 1 {
+            let mut fields = data
+                .chunks_exact(8)
+                .map(|field| u64::from_le_bytes(field.try_into().unwrap()));
+            if size == u32::MAX as u64 {
+                size = fields.next().unwrap_or(size);
+            }
+            if compressed_size == u32::MAX as u64 {
+                compressed_size = fields.next().unwrap_or(compressed_size);
+            }
+        }
+        at += 4 + len;
+    }
+    return (size, compressed_size);
+}
+
+impl<T: Ord + Copy + Default + NpyDistance> DistanceMatrice<T> {
+    ///Read a dissimilarity matrice from a `.npz` archive, as written by `numpy.savez`, see `read_npy`
+    ///
+    /// `name` is the keyword the array was saved with (`arr_0` for the first unnamed one), `None` reads the first array of the archive. Compressed archives (`numpy.savez_compressed`) aren't supported.
+    pub fn read_npz(reader: impl Read, name: Option<&str>) -> Result<DistanceMatrice<T>, NpyError> {
+        return DistanceMatrice::read_npz_checked(reader, name, false);
+    }
+
+    ///Read a similarity matrice from a `.npz` archive, as `read_npz` does but checking it as `read_npy_similarities` does
+    pub fn read_npz_similarities(
+        reader: impl Read,
+        name: Option<&str>,
+    ) -> Result<DistanceMatrice<T>, NpyError> {
+        return DistanceMatrice::read_npz_checked(reader, name, true);
+    }
+
+    fn read_npz_checked(
+        mut reader: impl Read,
+        name: Option<&str>,
+        similarities: bool,
+    ) -> Result<DistanceMatrice<T>, NpyError> {
+        loop {
+            let mut signature = [0; 4];
+            reader.read_exact(&mut signature)?;
+            match u32::from_le_bytes(signature) {
+                LOCAL_HEADER => {}
+                CENTRAL_HEADER | END_OF_CENTRAL_DIRECTORY => {
+                    return Err(NpyError::MissingArray {
+                        name: name.unwrap_or_default().to_string(),
+                    })
+                }
+                _ => return Err(NpyError::NotNpy),
+            }
+            let mut header = [0; 26];
+            reader.read_exact(&mut header)?;
+            let (flags, method) = (read_u16(&header, 2), read_u16(&header, 4));
+            let mut entry_name = vec![0; read_u16(&header, 22) as usize];
+            reader.read_exact(&mut entry_name)?;
+            let mut extra = vec![0; read_u16(&header, 24) as usize];
+            reader.read_exact(&mut extra)?;
+            let (_, compressed_size) =
+                zip64_sizes(&extra, read_u32(&header, 18), read_u32(&header, 14));
+            if flags & 8 != 0 && compressed_size == 0 {
+                // The size is only known after the data, which can't be skipped without decompressing it
+                return Err(NpyError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "npz entries without their size in the local header aren't supported",
+                )));
+            }
+
+            let entry_name = String::from_utf8_lossy(&entry_name);
+            let array_name = entry_name.strip_suffix(".npy").unwrap_or(&entry_name);
+            let mut data = (&mut reader).take(compressed_size);
+            if name.map_or(true, |name| name == array_name) {
+                if method != 0 {
+                    return Err(NpyError::Compressed {
+                        name: array_name.to_string(),
+                    });
+                }
+                return if similarities {
+                    DistanceMatrice::read_npy_similarities(data)
+                } else {
+                    DistanceMatrice::read_npy(data)
+                };
+            }
+            io::copy(&mut data, &mut io::sink())?;
+        }
+    }
+}
+
+/// Writes arrays to a `.npz` archive, which `numpy.load` reads as a dict of arrays.
+///
+/// Arrays are stored uncompressed, as `numpy.savez` does. Each one is built in memory before being written, and must be less than 4 GiB.
+pub struct NpzWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    /// Name, CRC-32, size and offset of each array written
+    entries: Vec<(String, u32, u32, u32)>,
+}
+
+impl<W: Write> NpzWriter<W> {
+    pub fn new(writer: W) -> NpzWriter<W> {
+        return NpzWriter {
+            writer,
+            offset: 0,
+            entries: Vec::new(),
+        };
+    }
+
+    ///Add the matrice as a square array named `name`, see `DistanceMatrice::write_npy`
+    pub fn add_matrice<T: Ord + Copy + NpyDistance>(
+        &mut self,
+        name: &str,
+        dist: &DistanceMatrice<T>,
+    ) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        dist.write_npy(&mut bytes)?;
+        return self.add(name, bytes);
+    }
+
+    ///Add the matrice as a condensed vector named `name`, see `DistanceMatrice::write_npy_condensed`
+    pub fn add_condensed<T: Ord + Copy + NpyDistance>(
+        &mut self,
+        name: &str,
+        dist: &DistanceMatrice<T>,
+    ) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        dist.write_npy_condensed(&mut bytes)?;
+        return self.add(name, bytes);
+    }
+
+    ///Add the permutation as a vector of `int64` named `name`
+    pub fn add_permutation(&mut self, name: &str, permut: &Permutation) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        permut.write_npy(&mut bytes)?;
+        return self.add(name, bytes);
+    }
+
+    fn add(&mut self, name: &str, bytes: Vec<u8>) -> io::Result<()> {
+        let name = format!("{name}.npy");
+        let too_large = |what: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{what} too large for a npz archive, write a npy file instead"),
+            )
+        };
+        let size = u32::try_from(bytes.len()).map_err(|_| too_large("array"))?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large("archive"))?;
+        let crc = crc32(&bytes);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        for field in [20, 0, 0, 0, DOS_DATE] {
+            header.extend_from_slice(&u16::to_le_bytes(field));
+        }
+        for field in [crc, size, size] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(name.as_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&bytes)?;
+
+        self.offset += (header.len() + bytes.len()) as u64;
+        self.entries.push((name, crc, size, offset));
+        return Ok(());
+    }
+
+    ///Write the directory of the archive, returning the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut directory = Vec::new();
+        for (name, crc, size, offset) in &self.entries {
+            directory.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+            for field in [20, 20, 0, 0, 0, DOS_DATE] {
+                directory.extend_from_slice(&u16::to_le_bytes(field));
+            }
+            for field in [*crc, *size, *size] {
+                directory.extend_from_slice(&field.to_le_bytes());
+            }
+            for field in [name.len() as u16, 0, 0, 0, 0] {
+                directory.extend_from_slice(&field.to_le_bytes());
+            }
+            for field in [0, *offset] {
+                directory.extend_from_slice(&u32::to_le_bytes(field));
+            }
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let directory_offset = u32::try_from(self.offset).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "archive too large for a npz archive, write a npy file instead",
+            )
+        })?;
+
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        let entries = self.entries.len() as u16;
+        for field in [0, 0, entries, entries] {
+            end.extend_from_slice(&u16::to_le_bytes(field));
+        }
+        for field in [directory.len() as u32, directory_offset] {
+            end.extend_from_slice(&field.to_le_bytes());
+        }
+        end.extend_from_slice(&0u16.to_le_bytes());
+
+        self.writer.write_all(&directory)?;
+        self.writer.write_all(&end)?;
+        return Ok(self.writer);
+    }
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    assert_eq!(crc32(b""), 0);
+}
+
+#[test]
+fn test_read_write_npz() {
+    let dist = DistanceMatrice::try_from(vec![
+        vec![0, 1, 3, 4],
+        vec![0, 0, 2, 3],
+        vec![0, 0, 0, 1],
+        vec![0, 0, 0, 0],
+    ])
+    .unwrap();
+    let permut = Permutation::try_from(vec![3, 2, 1, 0]).unwrap();
+
+    let mut npz = NpzWriter::new(Vec::new());
+    npz.add_permutation("order", &permut).unwrap();
+    npz.add_matrice("dist", &dist).unwrap();
+    npz.add_condensed("condensed", &dist.permut_matrice(&permut))
+        .unwrap();
+    let archive = npz.finish().unwrap();
+
+    let read = DistanceMatrice::<u32>::read_npz(archive.as_slice(), Some("dist")).unwrap();
+    assert_eq!(read.to_condensed(), dist.to_condensed());
+    let read = DistanceMatrice::<u32>::read_npz(archive.as_slice(), Some("condensed")).unwrap();
+    assert_eq!(read.to_condensed(), vec![1, 3, 4, 2, 3, 1]);
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npz(archive.as_slice(), Some("missing")),
+        Err(NpyError::MissingArray { .. })
+    ));
+    // The first array is the permutation, 4 points can't be a condensed vector
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_npz(archive.as_slice(), None),
+        Err(NpyError::Matrice(_))
+    ));
+}
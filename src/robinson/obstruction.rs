@@ -0,0 +1,32 @@
+use std::fmt;
+
+use super::Violation;
+
+/// A certificate that a `DistanceMatrice` isn't Robinson, as found by `Robin::obstruction`.
+///
+/// No order of `points` (hence of the whole matrice) respects the Robinson property, and `points` is minimal : without any one of them the remaining points admit a compatible order.
+///
+/// `forbidden` lists every triple of `points` where `neighbour` can't lie between `i` and `j` since `d(i, j) < max(d(i, neighbour), d(neighbour, j))`. Any order of `points` puts a `neighbour` between its `i` and `j` for at least one of them, which can be checked without this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obstruction {
+    pub points: Vec<u32>,
+    pub forbidden: Vec<Violation>,
+}
+
+impl fmt::Display for Obstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "points {:?} admit no compatible order, any order of them puts a point between two others in one of these triples :",
+            self.points
+        )?;
+        for Violation { i, neighbour, j } in &self.forbidden {
+            writeln!(
+                f,
+                "  {neighbour} between {i} and {j} : d({i}, {j}) < max(d({i}, {neighbour}), d({neighbour}, {j}))"
+            )?;
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,70 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Floating point distance with a total order, so it can be used in a `DistanceMatrice`.
+///
+/// Values are compared as usual (`-0.0` and `0.0` are equal). NaN with a negative sign are smaller than any other value and NaN with a positive sign are greater than any other value.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct OrdFloat<F>(pub F);
+
+macro_rules! impl_ord_float {
+    ($float:ty) => {
+        impl Ord for OrdFloat<$float> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                return match self.0.partial_cmp(&other.0) {
+                    Some(ordering) => ordering,
+                    None => self.0.total_cmp(&other.0),
+                };
+            }
+        }
+
+        impl PartialOrd for OrdFloat<$float> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                return Some(self.cmp(other));
+            }
+        }
+
+        impl PartialEq for OrdFloat<$float> {
+            fn eq(&self, other: &Self) -> bool {
+                return self.cmp(other) == Ordering::Equal;
+            }
+        }
+
+        impl Eq for OrdFloat<$float> {}
+
+        impl From<$float> for OrdFloat<$float> {
+            fn from(value: $float) -> Self {
+                return OrdFloat(value);
+            }
+        }
+    };
+}
+
+impl_ord_float!(f32);
+impl_ord_float!(f64);
+
+impl<F: fmt::Display> fmt::Display for OrdFloat<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return fmt::Display::fmt(&self.0, f);
+    }
+}
+
+impl<F: FromStr> FromStr for OrdFloat<F> {
+    type Err = F::Err;
+
+    fn from_str(s: &str) -> Result<Self, F::Err> {
+        return s.parse().map(OrdFloat);
+    }
+}
+
+#[test]
+fn test_ord_float_cmp() {
+    assert!(OrdFloat(0.5) < OrdFloat(1.5));
+    assert_eq!(OrdFloat(-0.0), OrdFloat(0.0));
+    assert!(OrdFloat(f64::NAN) > OrdFloat(f64::INFINITY));
+    assert!(OrdFloat(-f64::NAN) < OrdFloat(f64::NEG_INFINITY));
+    assert_eq!(OrdFloat(f32::NAN), OrdFloat(f32::NAN));
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::ops::Index;
+
+/// An order of the points of a `DistanceMatrice`, points being numbered from 0.
+///
+/// `permutation[k]` is the point at position `k`. Applying it to a matrice (with `permut_matrice`) puts line `permutation[k]` at line `k`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Vec<u32>", into = "Vec<u32>"))]
+pub struct Permutation {
+    points: Vec<u32>,
+}
+
+/// Why a `Vec<u32>` isn't a `Permutation`, or two permutations can't be composed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermutationError {
+    /// `point` isn't less than the number of points `len`
+    OutOfRange { point: u32, len: usize },
+    /// `point` appears more than once
+    Duplicate { point: u32 },
+    /// Composed permutations have `len` and `expected` points
+    LengthMismatch { len: usize, expected: usize },
+}
+
+impl fmt::Display for PermutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PermutationError::OutOfRange { point, len } => write!(
+                f,
+                "{point} can't be in a permutation of {len} points, they are numbered from 0"
+            ),
+            PermutationError::Duplicate { point } => {
+                write!(f, "{point} appears more than once in the permutation")
+            }
+            PermutationError::LengthMismatch { len, expected } => write!(
+                f,
+                "can't compose permutations of {len} and {expected} points"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PermutationError {}
+
+impl Permutation {
+    ///Return the permutation keeping the `n` points in place
+    pub fn identity(n: usize) -> Permutation {
+        return Permutation {
+            points: (0..n as u32).collect(),
+        };
+    }
+
+    /// For orders built by the crate, which are known to be permutations
+    pub(crate) fn from_vec_unchecked(points: Vec<u32>) -> Permutation {
+        debug_assert!(Permutation::try_from(points.clone()).is_ok());
+        return Permutation { points };
+    }
+
+    ///Create a permutation from points numbered from 1, as they were before this crate used `Permutation`
+    pub fn from_one_based(points: Vec<u32>) -> Result<Permutation, PermutationError> {
+        let len = points.len();
+        let zero_based = points
+            .into_iter()
+            .map(|point| match point.checked_sub(1) {
+                Some(point) => Ok(point),
+                None => Err(PermutationError::OutOfRange { point, len }),
+            })
+            .collect::<Result<Vec<u32>, PermutationError>>()?;
+        return Permutation::try_from(zero_based);
+    }
+
+    ///Return the points numbered from 1
+    pub fn to_one_based(&self) -> Vec<u32> {
+        return self.points.iter().map(|&point| point + 1).collect();
+    }
+
+    ///Return the number of points
+    pub fn len(&self) -> usize {
+        return self.points.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.points.is_empty();
+    }
+
+    pub fn as_slice(&self) -> &[u32] {
+        return &self.points;
+    }
+
+    pub fn into_vec(self) -> Vec<u32> {
+        return self.points;
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, u32> {
+        return self.points.iter();
+    }
+
+    ///Return the permutation whose position `k` holds `self[other[k]]`
+    ///
+    /// Applying `self` then `other` to a matrice is the same as applying `self.compose(other)`.
+    pub fn compose(&self, other: &Permutation) -> Result<Permutation, PermutationError> {
+        if self.len() != other.len() {
+            return Err(PermutationError::LengthMismatch {
+                len: self.len(),
+                expected: other.len(),
+            });
+        }
+        return Ok(Permutation {
+            points: other
+                .points
+                .iter()
+                .map(|&k| self.points[k as usize])
+                .collect(),
+        });
+    }
+
+    ///Return the permutation giving the position of each point, `self.compose(&self.inverse())` is the identity
+    pub fn inverse(&self) -> Permutation {
+        let mut positions = vec![0; self.len()];
+        for (position, &point) in self.points.iter().enumerate() {
+            positions[point as usize] = position as u32;
+        }
+        return Permutation { points: positions };
+    }
+
+    ///Return the same order read backward
+    pub fn reversed(&self) -> Permutation {
+        let mut points = self.points.clone();
+        points.reverse();
+        return Permutation { points };
+    }
+}
+
+/// Checks every point from 0 to `points.len() - 1` appears exactly once
+impl TryFrom<Vec<u32>> for Permutation {
+    type Error = PermutationError;
+
+    fn try_from(points: Vec<u32>) -> Result<Permutation, PermutationError> {
+        let mut seen = vec![false; points.len()];
+        for &point in &points {
+            match seen.get_mut(point as usize) {
+                None => {
+                    return Err(PermutationError::OutOfRange {
+                        point,
+                        len: points.len(),
+                    })
+                }
+                Some(true) => return Err(PermutationError::Duplicate { point }),
+                Some(seen_point) => *seen_point = true,
+            }
+        }
+        return Ok(Permutation { points });
+    }
+}
+
+impl From<Permutation> for Vec<u32> {
+    fn from(permutation: Permutation) -> Vec<u32> {
+        return permutation.points;
+    }
+}
+
+impl Index<usize> for Permutation {
+    type Output = u32;
+
+    fn index(&self, position: usize) -> &u32 {
+        return &self.points[position];
+    }
+}
+
+impl<'a> IntoIterator for &'a Permutation {
+    type Item = &'a u32;
+    type IntoIter = std::slice::Iter<'a, u32>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, u32> {
+        return self.points.iter();
+    }
+}
+
+#[test]
+fn test_permutation_validation() {
+    assert!(Permutation::try_from(vec![2, 0, 1]).is_ok());
+    assert_eq!(
+        Permutation::try_from(vec![0, 3, 1]),
+        Err(PermutationError::OutOfRange { point: 3, len: 3 })
+    );
+    assert_eq!(
+        Permutation::try_from(vec![0, 1, 1]),
+        Err(PermutationError::Duplicate { point: 1 })
+    );
+    assert_eq!(
+        Permutation::from_one_based(vec![3, 1, 2]),
+        Permutation::try_from(vec![2, 0, 1])
+    );
+    assert_eq!(
+        Permutation::from_one_based(vec![0, 1]),
+        Err(PermutationError::OutOfRange { point: 0, len: 2 })
+    );
+    assert_eq!(
+        Permutation::try_from(vec![2, 0, 1]).unwrap().to_one_based(),
+        vec![3, 1, 2]
+    );
+}
+
+#[test]
+fn test_permutation_operations() {
+    let permutation = Permutation::try_from(vec![2, 0, 3, 1]).unwrap();
+    let other = Permutation::try_from(vec![1, 3, 0, 2]).unwrap();
+
+    assert_eq!(
+        permutation.compose(&other).unwrap().as_slice(),
+        &[0, 1, 2, 3]
+    );
+    assert_eq!(permutation.inverse(), other);
+    assert_eq!(
+        permutation.compose(&permutation.inverse()).unwrap(),
+        Permutation::identity(4)
+    );
+    assert_eq!(permutation.reversed().as_slice(), &[1, 3, 0, 2]);
+    assert_eq!(
+        permutation.compose(&Permutation::identity(2)),
+        Err(PermutationError::LengthMismatch {
+            len: 4,
+            expected: 2
+        })
+    );
+}
@@ -0,0 +1,456 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use super::{Dissimilarity, DistanceMatrice, LabeledMatrice, MatriceError, Triangle};
+
+/// Width of taxon names in strict PHYLIP files
+const STRICT_NAME_WIDTH: usize = 10;
+
+/// How taxon names are laid out in a PHYLIP file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhylipNames {
+    /// Names take exactly the first 10 characters of their line, padded with spaces, and may contain spaces
+    Strict,
+    /// Names are separated from the distances by spaces, and can't contain any
+    Relaxed,
+}
+
+/// How a dissimilarity matrice is laid out in a PHYLIP file, see `DistanceMatrice::read_phylip` and `write_phylip`.
+///
+/// The file starts with the number of taxa, then each taxon name is followed by its distances, which may continue on the following lines. Unlike CSV files, triangular PHYLIP matrices don't hold the diagonal : line `i` of a `Lower` matrice holds `d(i, 0), ..., d(i, i - 1)` and line `i` of an `Upper` one `d(i, i + 1), ..., d(i, n - 1)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhylipFormat {
+    pub names: PhylipNames,
+    pub triangle: Triangle,
+    /// The file holds similarities, read with `Similarity` : the diagonal of a square matrice isn't checked
+    pub similarities: bool,
+}
+
+impl PhylipFormat {
+    ///Square matrice with strict names
+    pub fn strict() -> PhylipFormat {
+        return PhylipFormat {
+            names: PhylipNames::Strict,
+            triangle: Triangle::Full,
+            similarities: false,
+        };
+    }
+
+    ///Square matrice with relaxed names
+    pub fn relaxed() -> PhylipFormat {
+        return PhylipFormat {
+            names: PhylipNames::Relaxed,
+            triangle: Triangle::Full,
+            similarities: false,
+        };
+    }
+
+    pub fn with_triangle(mut self, triangle: Triangle) -> PhylipFormat {
+        self.triangle = triangle;
+        return self;
+    }
+
+    pub fn with_similarities(mut self, similarities: bool) -> PhylipFormat {
+        self.similarities = similarities;
+        return self;
+    }
+
+    /// Number of distances on the line of taxon `i` out of `n`
+    fn distances_of(&self, i: usize, n: usize) -> usize {
+        match self.triangle {
+            Triangle::Full => n,
+            Triangle::Lower => i,
+            Triangle::Upper => n - i - 1,
+        }
+    }
+}
+
+/// Why a PHYLIP file couldn't be read as a `DistanceMatrice`. Lines are numbered from 1, as in a text editor.
+#[derive(Debug)]
+pub enum PhylipError {
+    Io(io::Error),
+    /// The first line doesn't start with the number of taxa
+    TaxonCount {
+        line: usize,
+        value: String,
+    },
+    /// `value`, on line `line`, isn't a distance
+    Parse {
+        line: usize,
+        value: String,
+    },
+    /// The file ends before the `expected` distances of `taxon`, only `len` were found
+    MissingDistances {
+        taxon: String,
+        len: usize,
+        expected: usize,
+    },
+    /// The line of `taxon` has `len` distances instead of `expected`
+    TooManyDistances {
+        line: usize,
+        taxon: String,
+        len: usize,
+        expected: usize,
+    },
+    /// The file ends before the `expected` taxa, only `len` were found
+    MissingTaxa {
+        len: usize,
+        expected: usize,
+    },
+    /// The distances read don't form a valid matrice (empty, asymmetric, ...)
+    Matrice(MatriceError),
+}
+
+impl fmt::Display for PhylipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhylipError::Io(error) => write!(f, "can't read the file : {error}"),
+            PhylipError::TaxonCount { line, value } => {
+                write!(f, "line {line} : {value:?} isn't a number of taxa")
+            }
+            PhylipError::Parse { line, value } => {
+                write!(f, "line {line} : {value:?} isn't a distance")
+            }
+            PhylipError::MissingDistances {
+                taxon,
+                len,
+                expected,
+            } => write!(
+                f,
+                "the file ends after {len} distances of taxon {taxon:?} instead of {expected}"
+            ),
+            PhylipError::TooManyDistances {
+                line,
+                taxon,
+                len,
+                expected,
+            } => write!(
+                f,
+                "line {line} : taxon {taxon:?} has {len} distances instead of {expected}"
+            ),
+            PhylipError::MissingTaxa { len, expected } => {
+                write!(f, "the file ends after {len} taxa instead of {expected}")
+            }
+            PhylipError::Matrice(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for PhylipError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            PhylipError::Io(error) => Some(error),
+            PhylipError::Matrice(error) => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for PhylipError {
+    fn from(error: io::Error) -> PhylipError {
+        return PhylipError::Io(error);
+    }
+}
+
+impl From<MatriceError> for PhylipError {
+    fn from(error: MatriceError) -> PhylipError {
+        return PhylipError::Matrice(error);
+    }
+}
+
+/// Split the line of a taxon into its name and the rest of the line
+fn split_name(text: &str, names: PhylipNames) -> (String, &str) {
+    return match names {
+        PhylipNames::Strict => {
+            let end = text
+                .char_indices()
+                .nth(STRICT_NAME_WIDTH)
+                .map_or(text.len(), |(index, _)| index);
+            (text[..end].trim().to_string(), &text[end..])
+        }
+        PhylipNames::Relaxed => {
+            let text = text.trim_start();
+            let end = text.find(char::is_whitespace).unwrap_or(text.len());
+            (text[..end].to_string(), &text[end..])
+        }
+    };
+}
+
+impl<T: Ord + Copy + Default + FromStr> DistanceMatrice<T> {
+    ///Read a dissimilarity matrice from a PHYLIP file laid out as `format` tells, keeping the taxon names as labels
+    ///
+    /// Square matrices are checked as `new_checked` does, so their two triangles must be equal, or as `new_checked_similarities` does if the format holds similarities. Only the first matrice of the file is read.
+    pub fn read_phylip(
+        reader: impl BufRead,
+        format: &PhylipFormat,
+    ) -> Result<LabeledMatrice<T>, PhylipError> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .map(|(index, text)| text.map(|text| (index + 1, text)))
+            .filter(|line| !matches!(line, Ok((_, text)) if text.trim().is_empty()));
+
+        let n = match lines.next().transpose()? {
+            None => return Err(PhylipError::Matrice(MatriceError::Empty)),
+            Some((line, text)) => {
+                let value = text.split_whitespace().next().unwrap_or_default();
+                value
+                    .parse::<usize>()
+                    .map_err(|_| PhylipError::TaxonCount {
+                        line,
+                        value: value.to_string(),
+                    })?
+            }
+        };
+
+        // The taxon count isn't trusted to allocate, rows are stored as they are read
+        let mut names = Vec::new();
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for i in 0..n {
+            let (mut line, text) = match lines.next().transpose()? {
+                Some(line) => line,
+                None => {
+                    return Err(PhylipError::MissingTaxa {
+                        len: i,
+                        expected: n,
+                    })
+                }
+            };
+            let (name, rest) = split_name(&text, format.names);
+            let expected = format.distances_of(i, n);
+            let mut values: Vec<(usize, String)> = rest
+                .split_whitespace()
+                .map(|value| (line, value.to_string()))
+                .collect();
+            while values.len() < expected {
+                let (next_line, text) = match lines.next().transpose()? {
+                    Some(line) => line,
+                    None => {
+                        return Err(PhylipError::MissingDistances {
+                            taxon: name,
+                            len: values.len(),
+                            expected,
+                        })
+                    }
+                };
+                line = next_line;
+                values.extend(
+                    text.split_whitespace()
+                        .map(|value| (line, value.to_string())),
+                );
+            }
+            if values.len() > expected {
+                return Err(PhylipError::TooManyDistances {
+                    line,
+                    taxon: name,
+                    len: values.len(),
+                    expected,
+                });
+            }
+
+            let row = values
+                .into_iter()
+                .map(|(line, value)| {
+                    value
+                        .parse()
+                        .map_err(|_| PhylipError::Parse { line, value })
+                })
+                .collect::<Result<Vec<T>, PhylipError>>()?;
+            rows.push(row);
+            names.push(name);
+        }
+
+        let mut distance_mat = vec![vec![T::default(); n]; n];
+        for (i, row) in rows.into_iter().enumerate() {
+            let first = match format.triangle {
+                Triangle::Upper => i + 1,
+                _ => 0,
+            };
+            for (k, d) in row.into_iter().enumerate() {
+                let j = first + k;
+                if format.triangle == Triangle::Lower {
+                    distance_mat[j][i] = d;
+                } else {
+                    distance_mat[i][j] = d;
+                }
+            }
+        }
+
+        let dist = if format.similarities {
+            DistanceMatrice::new_checked_similarities(distance_mat)?
+        } else {
+            DistanceMatrice::new_checked(distance_mat)?
+        };
+        return Ok(LabeledMatrice {
+            dist,
+            labels: Some(names),
+        });
+    }
+}
+
+impl<T: Ord + Copy + fmt::Display> DistanceMatrice<T> {
+    ///Write the matrice to a PHYLIP file laid out as `format` tells, see `read_phylip`
+    ///
+    /// Taxa are named after `names`, or numbered from 0 if there are none. Returns an `InvalidInput` error if a name can't be read back : longer than 10 characters for strict names, empty or containing spaces for relaxed ones.
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if `names` doesn't have as many names as the matrice has points
+    pub fn write_phylip(
+        &self,
+        mut writer: impl Write,
+        format: &PhylipFormat,
+        names: Option<&[String]>,
+    ) -> io::Result<()> {
+        let n = self.len();
+        if let Some(names) = names {
+            assert_eq!(names.len(), n, "names and distance matrice sizes differ");
+        }
+
+        writeln!(writer, "{n}")?;
+        for i in 0..n {
+            let name = match names {
+                Some(names) => names[i].clone(),
+                None => i.to_string(),
+            };
+            let valid = match format.names {
+                PhylipNames::Strict => name.chars().count() <= STRICT_NAME_WIDTH,
+                PhylipNames::Relaxed => !name.is_empty() && !name.contains(char::is_whitespace),
+            };
+            if !valid {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{name:?} can't be a {:?} PHYLIP taxon name", format.names),
+                ));
+            }
+
+            match format.names {
+                PhylipNames::Strict => write!(writer, "{name:<STRICT_NAME_WIDTH$}")?,
+                PhylipNames::Relaxed => write!(writer, "{name}")?,
+            }
+            let first = match format.triangle {
+                Triangle::Upper => i + 1,
+                _ => 0,
+            };
+            for j in first..first + format.distances_of(i, n) {
+                write!(writer, " {}", self.d(i as u32, j as u32))?;
+            }
+            writeln!(writer)?;
+        }
+        return Ok(());
+    }
+}
+
+#[test]
+fn test_read_write_phylip() {
+    let upper = vec![
+        vec![0, 1, 3, 4],
+        vec![0, 0, 2, 3],
+        vec![0, 0, 0, 1],
+        vec![0, 0, 0, 0],
+    ];
+    let names: Vec<String> = ["Homo sap", "Pan", "Gorilla", "Pongo"]
+        .map(String::from)
+        .to_vec();
+
+    let strict =
+        "  4\nHomo sap  0 1 3 4\nPan       1 0 2 3\nGorilla   3 2\n 0 1\nPongo     4 3 1 0\n";
+    let read =
+        DistanceMatrice::<u32>::read_phylip(strict.as_bytes(), &PhylipFormat::strict()).unwrap();
+    let dist = DistanceMatrice::try_from(upper.clone()).unwrap();
+    assert_eq!(read.dist.to_condensed(), dist.to_condensed());
+    assert_eq!(read.labels.as_ref(), Some(&names));
+
+    // The order found is reported by name
+    let order = super::Robin::from(&read.dist).solve().unwrap();
+    assert_eq!(
+        read.labels_in_order(&order.permut).unwrap().join(" "),
+        "Homo sap Pan Gorilla Pongo"
+    );
+
+    let lower = "4\nA\nB 1\nC 3 2\nD 4 3 1\n";
+    let format = PhylipFormat::relaxed().with_triangle(Triangle::Lower);
+    let read = DistanceMatrice::<u32>::read_phylip(lower.as_bytes(), &format).unwrap();
+    assert_eq!(read.dist.to_rows(), upper);
+    let mut written = Vec::new();
+    read.dist
+        .write_phylip(&mut written, &format, read.labels.as_deref())
+        .unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), lower);
+
+    // Round trip through every layout
+    for names_format in [PhylipNames::Strict, PhylipNames::Relaxed] {
+        for triangle in [Triangle::Full, Triangle::Upper, Triangle::Lower] {
+            let format = PhylipFormat {
+                names: names_format,
+                triangle,
+                similarities: false,
+            };
+            let names: Vec<String> = ["a", "bb", "ccc", "dddd"].map(String::from).to_vec();
+            let mut written = Vec::new();
+            dist.write_phylip(&mut written, &format, Some(&names))
+                .unwrap();
+            let read = DistanceMatrice::<u32>::read_phylip(written.as_slice(), &format).unwrap();
+            assert_eq!(read.dist.to_condensed(), dist.to_condensed());
+            assert_eq!(read.labels, Some(names));
+        }
+    }
+
+    assert!(dist
+        .write_phylip(Vec::new(), &PhylipFormat::relaxed(), Some(&names))
+        .is_err());
+    assert!(dist
+        .write_phylip(Vec::new(), &PhylipFormat::strict(), Some(&names))
+        .is_ok());
+
+    let read = |text: &str, format: PhylipFormat| {
+        return DistanceMatrice::<u32>::read_phylip(text.as_bytes(), &format).err();
+    };
+    assert!(matches!(
+        read("four\n", PhylipFormat::relaxed()),
+        Some(PhylipError::TaxonCount { line: 1, .. })
+    ));
+    assert!(matches!(
+        read("2\nA 0 1\nB 1 x\n", PhylipFormat::relaxed()),
+        Some(PhylipError::Parse { line: 3, ref value }) if value == "x"
+    ));
+    assert!(matches!(
+        read("2\nA 0 1\nB 1\n", PhylipFormat::relaxed()),
+        Some(PhylipError::MissingDistances { len: 1, expected: 2, ref taxon }) if taxon == "B"
+    ));
+    assert!(matches!(
+        read("2\nA 0 1 2\nB 1 0\n", PhylipFormat::relaxed()),
+        Some(PhylipError::TooManyDistances {
+            line: 2,
+            len: 3,
+            expected: 2,
+            ..
+        })
+    ));
+    assert!(matches!(
+        read(
+            "3\nA\nB 1\n",
+            PhylipFormat::relaxed().with_triangle(Triangle::Lower)
+        ),
+        Some(PhylipError::MissingTaxa {
+            len: 2,
+            expected: 3
+        })
+    ));
+    assert!(matches!(
+        read("99999999999\nA 0\n", PhylipFormat::relaxed()),
+        Some(PhylipError::MissingDistances {
+            len: 1,
+            expected: 99999999999,
+            ..
+        })
+    ));
+    assert!(matches!(
+        read("99999999999\n", PhylipFormat::relaxed()),
+        Some(PhylipError::MissingTaxa {
+            len: 0,
+            expected: 99999999999
+        })
+    ));
+}
@@ -0,0 +1,653 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use super::Permutation;
+
+/// A node of a `PqTree`, children are indices of `PqTree::nodes`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PqNode {
+    Leaf(u32),
+    /// Children can be put in any order
+    P(Vec<usize>),
+    /// Children order is fixed, up to a reversal
+    Q(Vec<usize>),
+}
+
+/// Represents every compatible order of a Robinson `DistanceMatrice`.
+///
+/// The compatible orders are the orders of the leaves obtained by reordering the children of every P node and reversing the children of any Q node. Nodes are stored in `nodes`, the root first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PqTree {
+    nodes: Vec<PqNode>,
+}
+
+impl PqTree {
+    /// Build the tree of the orders where every interval of `intervals` stays consecutive
+    ///
+    /// Intervals are given as `(first, last)` positions in `order`, which must already keep all of them consecutive.
+    pub(crate) fn from_intervals(order: &[u32], mut intervals: Vec<(usize, usize)>) -> PqTree {
+        if order.is_empty() {
+            return PqTree { nodes: vec![] };
+        }
+        // Single points never constrain anything, they are the leaves anyway
+        intervals.retain(|&(first, last)| first < last);
+        intervals.sort_by_key(|&(first, last)| (first, Reverse(last)));
+        intervals.dedup();
+
+        // Every set of overlapping intervals (intersecting without one containing the other) has its union as a Q node, its intervals fix the order of the parts they cut it in.
+        // Other intervals are P nodes, their order is only fixed by the nodes they contain.
+        let roots = overlap_components(&intervals);
+        let mut components: Vec<Option<RawNode>> = vec![None; intervals.len()];
+        for (k, &(first, last)) in intervals.iter().enumerate() {
+            let component = components[roots[k]].get_or_insert(RawNode {
+                first,
+                last,
+                cuts: vec![],
+                intervals: 0,
+                children: vec![],
+            });
+            component.first = component.first.min(first);
+            component.last = component.last.max(last);
+            component.cuts.push(first);
+            component.cuts.push(last + 1);
+            component.intervals += 1;
+        }
+
+        let mut raw_nodes: Vec<RawNode> = components.into_iter().flatten().collect();
+        raw_nodes.extend((0..order.len()).map(|position| RawNode {
+            first: position,
+            last: position,
+            cuts: vec![],
+            intervals: 0,
+            children: vec![],
+        }));
+        raw_nodes.push(RawNode {
+            first: 0,
+            last: order.len() - 1,
+            cuts: vec![],
+            intervals: 0,
+            children: vec![],
+        });
+        // An interval which is also the union of overlapping ones is the Q node
+        raw_nodes.sort_by_key(|node| (node.first, Reverse(node.last), Reverse(node.intervals)));
+        raw_nodes.dedup_by_key(|node| (node.first, node.last));
+
+        // The unions are laminar, each node is the child of the smallest node containing it
+        let mut stack: Vec<usize> = Vec::new();
+        for k in 0..raw_nodes.len() {
+            while let Some(&top) = stack.last() {
+                if raw_nodes[top].last >= raw_nodes[k].first {
+                    break;
+                }
+                stack.pop();
+            }
+            if let Some(&parent) = stack.last() {
+                raw_nodes[parent].children.push(k);
+            }
+            stack.push(k);
+        }
+
+        let mut nodes: Vec<PqNode> = Vec::with_capacity(raw_nodes.len());
+        let mut atom_nodes: Vec<PqNode> = Vec::new();
+        for node in &raw_nodes {
+            if node.children.is_empty() {
+                nodes.push(PqNode::Leaf(order[node.first]));
+            } else if node.intervals < 2 {
+                nodes.push(PqNode::P(node.children.clone()));
+            } else {
+                // Nodes inside the Q node never cross the cuts of its intervals, the ones between two cuts can be put in any order
+                let mut cuts = node.cuts.clone();
+                cuts.sort();
+                cuts.dedup();
+                let mut q_children = Vec::new();
+                let mut atom: Vec<usize> = Vec::new();
+                let mut next_cut = 1;
+                for &child in &node.children {
+                    while raw_nodes[child].first >= cuts[next_cut] {
+                        q_children.push(atom_node(&mut atom, raw_nodes.len(), &mut atom_nodes));
+                        next_cut += 1;
+                    }
+                    atom.push(child);
+                }
+                q_children.push(atom_node(&mut atom, raw_nodes.len(), &mut atom_nodes));
+                nodes.push(PqNode::Q(q_children));
+            }
+        }
+        nodes.append(&mut atom_nodes);
+        return PqTree { nodes };
+    }
+
+    /// Tree made of `nodes`, which must already form a tree rooted at the first one
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_nodes_unchecked(nodes: Vec<PqNode>) -> PqTree {
+        return PqTree { nodes };
+    }
+
+    /// Return the tree of the orders of this tree where the points of `in_set` are consecutive, `None` if there is none
+    ///
+    /// Children are arranged bottom-up so that the points of the set end the frontier of each node, the order found then gives the new tree along with the constraints of this one.
+    pub(crate) fn reduce(self, in_set: &[bool]) -> Option<PqTree> {
+        if self.nodes.is_empty() {
+            return Some(self);
+        }
+        // Nodes before their children
+        let mut preorder = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            preorder.push(id);
+            if let PqNode::P(children) | PqNode::Q(children) = &self.nodes[id] {
+                stack.extend(children);
+            }
+        }
+        if self.keeps_consecutive(&preorder, in_set) {
+            return Some(self);
+        }
+
+        let mut pertinence = vec![Pertinence::Empty; self.nodes.len()];
+        // Children of each node from left to right, along with whether their frontier is reversed
+        let mut arrangements: Vec<Vec<(usize, bool)>> = vec![vec![]; self.nodes.len()];
+        for &id in preorder.iter().rev() {
+            let (kind, arrangement) = match &self.nodes[id] {
+                PqNode::Leaf(point) if in_set[*point as usize] => (Pertinence::Full, vec![]),
+                PqNode::Leaf(_) => (Pertinence::Empty, vec![]),
+                PqNode::P(children) => arrange_p(children, &pertinence)?,
+                PqNode::Q(children) => arrange_q(children, &pertinence)?,
+            };
+            pertinence[id] = kind;
+            arrangements[id] = arrangement;
+        }
+
+        let mut order = Vec::with_capacity(in_set.len());
+        let mut stack = vec![(0, false)];
+        while let Some((id, reversed)) = stack.pop() {
+            if let PqNode::Leaf(point) = self.nodes[id] {
+                order.push(point);
+                continue;
+            }
+            let children = arrangements[id]
+                .iter()
+                .map(|&(child, child_reversed)| (child, child_reversed != reversed));
+            if reversed {
+                stack.extend(children);
+            } else {
+                stack.extend(children.rev());
+            }
+        }
+
+        // The nodes of this tree keep their constraints : P nodes stay consecutive, and so do the neighbouring children of Q nodes
+        let mut position = vec![0; order.len()];
+        for (k, &point) in order.iter().enumerate() {
+            position[point as usize] = k;
+        }
+        let mut spans = vec![(0, 0); self.nodes.len()];
+        let mut intervals = Vec::new();
+        for &id in preorder.iter().rev() {
+            spans[id] = match &self.nodes[id] {
+                PqNode::Leaf(point) => (position[*point as usize], position[*point as usize]),
+                PqNode::P(children) | PqNode::Q(children) => {
+                    let first = children.iter().map(|&child| spans[child].0).min().unwrap();
+                    let last = children.iter().map(|&child| spans[child].1).max().unwrap();
+                    (first, last)
+                }
+            };
+            match &self.nodes[id] {
+                PqNode::Leaf(_) => {}
+                PqNode::P(_) => intervals.push(spans[id]),
+                PqNode::Q(children) => intervals.extend(children.windows(2).map(|pair| {
+                    let (left, right) = (spans[pair[0]], spans[pair[1]]);
+                    (left.0.min(right.0), left.1.max(right.1))
+                })),
+            }
+        }
+        let first = order.iter().position(|&point| in_set[point as usize]);
+        let last = order.iter().rposition(|&point| in_set[point as usize]);
+        if let (Some(first), Some(last)) = (first, last) {
+            intervals.push((first, last));
+        }
+        return Some(PqTree::from_intervals(&order, intervals));
+    }
+
+    /// Return true if the points of `in_set` are consecutive in every order of the tree : they are the leaves of a node, or of neighbouring children of a Q node
+    fn keeps_consecutive(&self, preorder: &[usize], in_set: &[bool]) -> bool {
+        // Number of leaves, and of leaves in the set, below each node
+        let mut counts = vec![(0, 0); self.nodes.len()];
+        for &id in preorder.iter().rev() {
+            counts[id] = match &self.nodes[id] {
+                PqNode::Leaf(point) => (1, in_set[*point as usize] as usize),
+                PqNode::P(children) | PqNode::Q(children) => {
+                    children.iter().fold((0, 0), |(leaves, in_set), &child| {
+                        (leaves + counts[child].0, in_set + counts[child].1)
+                    })
+                }
+            };
+        }
+        let total = counts[0].1;
+        if total == 0 {
+            return true;
+        }
+        // Go down to the smallest node holding the whole set
+        let mut id = 0;
+        while let PqNode::P(children) | PqNode::Q(children) = &self.nodes[id] {
+            match children.iter().find(|&&child| counts[child].1 == total) {
+                Some(&child) => id = child,
+                None => break,
+            }
+        }
+        return match &self.nodes[id] {
+            _ if counts[id].0 == total => true,
+            PqNode::Q(children) => {
+                let held: Vec<usize> = (0..children.len())
+                    .filter(|&k| counts[children[k]].1 > 0)
+                    .collect();
+                held.iter()
+                    .all(|&k| counts[children[k]].1 == counts[children[k]].0)
+                    && held.last().unwrap() - held[0] + 1 == held.len()
+            }
+            _ => false,
+        };
+    }
+
+    ///Return every node of the tree, the root is the first one
+    pub fn nodes(&self) -> &[PqNode] {
+        return &self.nodes;
+    }
+
+    ///Return the root of the tree, `None` for an empty matrice
+    pub fn root(&self) -> Option<&PqNode> {
+        return self.nodes.first();
+    }
+
+    ///Return the node at index `id` of `nodes`
+    pub fn node(&self, id: usize) -> &PqNode {
+        return &self.nodes[id];
+    }
+
+    ///Return the leaves from left to right, a compatible order
+    pub fn frontier(&self) -> Permutation {
+        return self.frontier_with(|_, children| children.to_vec());
+    }
+
+    /// Return the leaves from left to right, `arrange` giving the order of the children of each node
+    fn frontier_with(&self, mut arrange: impl FnMut(usize, &[usize]) -> Vec<usize>) -> Permutation {
+        let mut frontier = Vec::new();
+        if self.nodes.is_empty() {
+            return Permutation::from_vec_unchecked(frontier);
+        }
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            match &self.nodes[id] {
+                PqNode::Leaf(point) => frontier.push(*point),
+                PqNode::P(children) | PqNode::Q(children) => {
+                    stack.extend(arrange(id, children).into_iter().rev())
+                }
+            }
+        }
+        return Permutation::from_vec_unchecked(frontier);
+    }
+
+    ///Return the number of compatible orders represented by the tree, saturating at `u128::MAX`
+    pub fn count_orders(&self) -> u128 {
+        let mut count: u128 = 1;
+        for node in &self.nodes {
+            match node {
+                PqNode::Leaf(_) => {}
+                PqNode::P(children) => {
+                    for k in 2..children.len() as u128 + 1 {
+                        count = count.saturating_mul(k);
+                    }
+                }
+                PqNode::Q(_) => count = count.saturating_mul(2),
+            }
+        }
+        return count;
+    }
+
+    ///Iterate over every compatible order represented by the tree
+    pub fn orders(&self) -> CompatibleOrders {
+        return CompatibleOrders::new(self.clone());
+    }
+}
+
+/// Bracket notation : `(...)` for P nodes and `[...]` for Q nodes
+impl fmt::Display for PqTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+        let mut stack = vec![Token::Node(0)];
+        while let Some(token) = stack.pop() {
+            match token {
+                Token::Node(id) => {
+                    let (children, open, close) = match &self.nodes[id] {
+                        PqNode::Leaf(point) => {
+                            write!(f, "{}", point)?;
+                            continue;
+                        }
+                        PqNode::P(children) => (children, "(", ")"),
+                        PqNode::Q(children) => (children, "[", "]"),
+                    };
+                    write!(f, "{}", open)?;
+                    stack.push(Token::Text(close));
+                    for (k, &child) in children.iter().enumerate().rev() {
+                        stack.push(Token::Node(child));
+                        if k > 0 {
+                            stack.push(Token::Text(" "));
+                        }
+                    }
+                }
+                Token::Text(text) => write!(f, "{}", text)?,
+            }
+        }
+        return Ok(());
+    }
+}
+
+enum Token {
+    Node(usize),
+    Text(&'static str),
+}
+
+/// Iterator over the compatible orders of a `PqTree`, see `Robin::compatible_orders`
+///
+/// Orders are computed one at a time, each one in O(n).
+pub struct CompatibleOrders {
+    tree: PqTree,
+    /// Current order of the children of each node, as positions in the node's children
+    arrangements: Vec<Vec<usize>>,
+    done: bool,
+}
+
+impl CompatibleOrders {
+    pub(crate) fn new(tree: PqTree) -> CompatibleOrders {
+        let arrangements = tree
+            .nodes
+            .iter()
+            .map(|node| match node {
+                PqNode::Leaf(_) => vec![],
+                PqNode::P(children) | PqNode::Q(children) => (0..children.len()).collect(),
+            })
+            .collect();
+        return CompatibleOrders {
+            tree,
+            arrangements,
+            done: false,
+        };
+    }
+
+    pub(crate) fn empty() -> CompatibleOrders {
+        let mut orders = CompatibleOrders::new(PqTree { nodes: vec![] });
+        orders.done = true;
+        return orders;
+    }
+
+    /// Move to the next arrangement, counting like an odometer. Return false once every arrangement was seen.
+    fn advance(&mut self) -> bool {
+        for (node, arrangement) in self.tree.nodes.iter().zip(self.arrangements.iter_mut()) {
+            let carry = match node {
+                PqNode::Leaf(_) => continue,
+                PqNode::P(_) => !next_permutation(arrangement),
+                PqNode::Q(_) => {
+                    arrangement.reverse();
+                    arrangement[0] == 0
+                }
+            };
+            if !carry {
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+impl Iterator for CompatibleOrders {
+    type Item = Permutation;
+
+    fn next(&mut self) -> Option<Permutation> {
+        if self.done {
+            return None;
+        }
+        let arrangements = &self.arrangements;
+        let order = self
+            .tree
+            .frontier_with(|id, children| arrangements[id].iter().map(|&k| children[k]).collect());
+        self.done = !self.advance();
+        return Some(order);
+    }
+}
+
+/// Rearrange `values` into the next permutation in lexicographic order. Return false, and sort `values`, if it was the last one.
+pub(crate) fn next_permutation(values: &mut [usize]) -> bool {
+    let Some(pivot) = (1..values.len()).rev().find(|&k| values[k - 1] < values[k]) else {
+        values.reverse();
+        return false;
+    };
+    let successor = (pivot..values.len())
+        .rev()
+        .find(|&k| values[k] > values[pivot - 1])
+        .unwrap();
+    values.swap(pivot - 1, successor);
+    values[pivot..].reverse();
+    return true;
+}
+
+/// Node of the tree while it's built, covering the positions `first..=last`
+#[derive(Clone)]
+struct RawNode {
+    first: usize,
+    last: usize,
+    /// Positions where the intervals of the node start or end, for Q nodes
+    cuts: Vec<usize>,
+    /// Number of overlapping intervals whose union is the node, Q nodes have at least 2
+    intervals: usize,
+    children: Vec<usize>,
+}
+
+/// Where the points of the set given to `PqTree::reduce` lie in the frontier of a node, once its children are arranged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pertinence {
+    Empty,
+    Full,
+    /// The points of the set end the frontier
+    End,
+    /// The points of the set are consecutive, away from both ends
+    Middle,
+}
+
+/// Arrange the children of a P node : empty children, the partial one ending with the set, the full ones, then the partial one starting with it
+fn arrange_p(
+    children: &[usize],
+    pertinence: &[Pertinence],
+) -> Option<(Pertinence, Vec<(usize, bool)>)> {
+    let of = |kind: Pertinence| -> Vec<usize> {
+        return children
+            .iter()
+            .copied()
+            .filter(|&child| pertinence[child] == kind)
+            .collect();
+    };
+    let (empty, full, end, middle) = (
+        of(Pertinence::Empty),
+        of(Pertinence::Full),
+        of(Pertinence::End),
+        of(Pertinence::Middle),
+    );
+    if !middle.is_empty() {
+        // The set lies inside a single child
+        let inside = middle.len() == 1 && full.is_empty() && end.is_empty();
+        return inside.then(|| {
+            (
+                Pertinence::Middle,
+                children.iter().map(|&child| (child, false)).collect(),
+            )
+        });
+    }
+    let kind = match (empty.is_empty(), full.is_empty(), end.len()) {
+        (_, _, 3..) => return None,
+        (_, true, 0) => Pertinence::Empty,
+        (true, false, 0) => Pertinence::Full,
+        (_, _, 2) => Pertinence::Middle,
+        _ => Pertinence::End,
+    };
+    let mut arrangement: Vec<(usize, bool)> =
+        empty.into_iter().map(|child| (child, false)).collect();
+    arrangement.extend(end.first().map(|&child| (child, false)));
+    arrangement.extend(full.into_iter().map(|child| (child, false)));
+    arrangement.extend(end.get(1).map(|&child| (child, true)));
+    return Some((kind, arrangement));
+}
+
+/// Arrange the children of a Q node, only reversing it and its partial children : the children holding the set must be consecutive, the inner ones full
+fn arrange_q(
+    children: &[usize],
+    pertinence: &[Pertinence],
+) -> Option<(Pertinence, Vec<(usize, bool)>)> {
+    let kinds: Vec<Pertinence> = children.iter().map(|&child| pertinence[child]).collect();
+    let mut arrangement: Vec<(usize, bool)> =
+        children.iter().map(|&child| (child, false)).collect();
+    let Some(a) = kinds.iter().position(|&kind| kind != Pertinence::Empty) else {
+        return Some((Pertinence::Empty, arrangement));
+    };
+    let b = kinds
+        .iter()
+        .rposition(|&kind| kind != Pertinence::Empty)
+        .unwrap();
+    let last = kinds.len() - 1;
+    if kinds[a] == Pertinence::Middle || kinds[b] == Pertinence::Middle {
+        return (a == b).then_some((Pertinence::Middle, arrangement));
+    }
+    if (a + 1..b).any(|k| kinds[k] != Pertinence::Full) {
+        return None;
+    }
+    if a == 0 && b == last && kinds.iter().all(|&kind| kind == Pertinence::Full) {
+        return Some((Pertinence::Full, arrangement));
+    }
+
+    let right = b == last && (a == b || kinds[b] == Pertinence::Full);
+    let left = a == 0 && (a == b || kinds[a] == Pertinence::Full);
+    // Partial children turn the set towards the other children holding it, or towards the end it reaches
+    if kinds[b] == Pertinence::End && (a < b || (left && !right)) {
+        arrangement[b].1 = true;
+    }
+    if right {
+        return Some((Pertinence::End, arrangement));
+    }
+    if left {
+        arrangement.reverse();
+        for (_, reversed) in arrangement.iter_mut() {
+            *reversed = !*reversed;
+        }
+        return Some((Pertinence::End, arrangement));
+    }
+    return Some((Pertinence::Middle, arrangement));
+}
+
+/// Return the child of a Q node for the nodes of `atom`, which is emptied. Several nodes are gathered under a new P node, stored in `atom_nodes` after the `offset` first nodes.
+fn atom_node(atom: &mut Vec<usize>, offset: usize, atom_nodes: &mut Vec<PqNode>) -> usize {
+    if atom.len() == 1 {
+        return atom.pop().unwrap();
+    }
+    atom_nodes.push(PqNode::P(std::mem::take(atom)));
+    return offset + atom_nodes.len() - 1;
+}
+
+/// Return the representative of the overlap component of each interval.
+///
+/// `intervals` must be sorted by first position then decreasing last position. Going through them in this order, the intervals still intersecting the current one, sorted by last position, are made of runs of intervals of the same component : an interval overlaps exactly the runs whose smallest last position comes before its own.
+fn overlap_components(intervals: &[(usize, usize)]) -> Vec<usize> {
+    let mut parents: Vec<usize> = (0..intervals.len()).collect();
+    // The run with the smallest last positions is at the end
+    let mut runs: Vec<(usize, BinaryHeap<Reverse<usize>>)> = Vec::new();
+
+    for (k, &(first, last)) in intervals.iter().enumerate() {
+        while let Some((_, lasts)) = runs.last_mut() {
+            while lasts.peek().is_some_and(|&Reverse(l)| l < first) {
+                lasts.pop();
+            }
+            if !lasts.is_empty() {
+                break;
+            }
+            runs.pop();
+        }
+
+        let mut lasts = BinaryHeap::from([Reverse(last)]);
+        while let Some((member, run_lasts)) = runs.last_mut() {
+            if run_lasts.peek().unwrap().0 >= last {
+                break;
+            }
+            let root = find(&mut parents, *member);
+            parents[root] = k;
+            lasts.append(run_lasts);
+            runs.pop();
+        }
+        runs.push((k, lasts));
+    }
+
+    return (0..intervals.len())
+        .map(|k| find(&mut parents, k))
+        .collect();
+}
+
+fn find(parents: &mut [usize], mut k: usize) -> usize {
+    while parents[k] != k {
+        parents[k] = parents[parents[k]];
+        k = parents[k];
+    }
+    return k;
+}
+
+#[test]
+fn test_overlap_components() {
+    let intervals = vec![(0, 3), (0, 1), (2, 4), (5, 7), (6, 7)];
+    let roots = overlap_components(&intervals);
+    assert_eq!(roots[0], roots[2]);
+    assert_ne!(roots[0], roots[1]);
+    assert_ne!(roots[0], roots[3]);
+    assert_ne!(roots[3], roots[4]);
+}
+
+#[test]
+fn test_pq_tree_from_intervals() {
+    let order = vec![4, 3, 2, 1, 0];
+    let tree = PqTree::from_intervals(&order, vec![(0, 2), (2, 3), (0, 1)]);
+
+    assert_eq!(tree.to_string(), "([(4 3) 2 1] 0)");
+    assert_eq!(tree.frontier().as_slice(), order);
+    assert_eq!(tree.count_orders(), 8);
+    let mut orders: Vec<Vec<u32>> = tree.orders().map(Vec::from).collect();
+    assert_eq!(orders.len(), 8);
+    orders.sort();
+    orders.dedup();
+    assert_eq!(orders.len(), 8);
+}
+
+#[test]
+fn test_next_permutation() {
+    let mut values = vec![0, 1, 2];
+    let mut count = 1;
+    while next_permutation(&mut values) {
+        count += 1;
+    }
+    assert_eq!(count, 6);
+    assert_eq!(values, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_pq_tree_reduce() {
+    let tree = PqTree::from_intervals(&[0, 1, 2, 3, 4], vec![]);
+    let set = |points: &[u32]| -> Vec<bool> { (0..5).map(|x| points.contains(&x)).collect() };
+
+    let tree = tree.reduce(&set(&[1, 3])).unwrap();
+    assert_eq!(tree.count_orders(), 24 * 2);
+    let tree = tree.reduce(&set(&[3, 4])).unwrap();
+    assert_eq!(tree.count_orders(), 6 * 2);
+    for order in tree.orders() {
+        let position = |x: u32| order.iter().position(|&y| y == x).unwrap();
+        assert_eq!(position(3).abs_diff(position(1)), 1);
+        assert_eq!(position(3).abs_diff(position(4)), 1);
+    }
+    // Already consecutive in every order
+    assert_eq!(tree.clone().reduce(&set(&[1, 3, 4])), Some(tree.clone()));
+    assert_eq!(tree.reduce(&set(&[1, 4])), None);
+}
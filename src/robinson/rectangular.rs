@@ -0,0 +1,248 @@
+use super::{Dissimilarity, MatriceError, Permutation, Robin, Similarity};
+
+/// A rectangular matrice of abundances or incidences, its rows and columns being two kinds of objects : archaeological contexts and artefact types, documents and terms, ...
+///
+/// Seriating it permutes the rows and the columns so that every row and every column is unimodal, growing then decreasing. For a 0/1 matrice, ones are then consecutive in every row and every column (Petrie's seriation).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RectangularMatrice<T = u32> {
+    rows: Vec<Vec<T>>,
+}
+
+/// Orders of the rows and of the columns of a `RectangularMatrice`, as found by `RectangularMatrice::seriate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Seriation {
+    pub rows: Permutation,
+    pub columns: Permutation,
+}
+
+/// Checks every line is as long as the first one
+impl<T> TryFrom<Vec<Vec<T>>> for RectangularMatrice<T> {
+    type Error = MatriceError;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self, MatriceError> {
+        let expected = rows.first().ok_or(MatriceError::Empty)?.len();
+        for len in [rows.len(), expected] {
+            if len > u32::MAX as usize {
+                return Err(MatriceError::TooLarge { len });
+            }
+        }
+        if let Some(row) = rows.iter().position(|line| line.len() != expected) {
+            return Err(MatriceError::NotRectangular {
+                row,
+                len: rows[row].len(),
+                expected,
+            });
+        }
+        return Ok(RectangularMatrice { rows });
+    }
+}
+
+/// Return true if `values` grow then decrease : none of them is smaller than both an earlier and a later one
+pub(crate) fn is_unimodal<T: Ord>(values: impl IntoIterator<Item = T>) -> bool {
+    let mut values = values.into_iter().peekable();
+    let mut growing = true;
+    while let Some(value) = values.next() {
+        match values.peek() {
+            Some(next) if growing && *next < value => growing = false,
+            Some(next) if !growing && *next > value => return false,
+            _ => {}
+        }
+    }
+    return true;
+}
+
+/// Co-occurrences of `n` lines in the sets of lines reaching each value of the lines of the other kind, computed when read rather than stored
+struct CoOccurrences {
+    /// Rank of the value of each line among the distinct values of each line of the other kind
+    ranks: Vec<Vec<u32>>,
+}
+
+impl Dissimilarity for CoOccurrences {
+    type Distance = u64;
+
+    fn len(&self) -> usize {
+        return self.ranks.len();
+    }
+
+    fn d(&self, i: u32, j: u32) -> u64 {
+        return self.ranks[i as usize]
+            .iter()
+            .zip(&self.ranks[j as usize])
+            .map(|(&rank_i, &rank_j)| rank_i.min(rank_j) as u64)
+            .sum();
+    }
+}
+
+/// Return an order of `n` lines along which each of the `m` lines of the other kind is unimodal, `value(i, k)` being the value of line `i` in line `k`. `None` if there is none.
+///
+/// Along such an order, the lines reaching a given value in line `k` are consecutive. The number of these sets holding both lines is then a Robinson similarity, so the order is one of its compatible orders, which are refined by every set.
+///
+/// The similarity is computed in O(m) each time it is read rather than stored, so this takes O(n²·m) time and O(n·m) memory.
+pub(crate) fn unimodal_order<T: Ord + Copy>(
+    n: usize,
+    m: usize,
+    value: impl Fn(usize, usize) -> T,
+) -> Option<Permutation> {
+    if n == 0 {
+        return Some(Permutation::identity(0));
+    }
+    let mut ranks = vec![Vec::with_capacity(m); n];
+    let mut max_ranks = Vec::with_capacity(m);
+    let mut values = Vec::with_capacity(n);
+    for k in 0..m {
+        values.clear();
+        values.extend((0..n).map(|i| value(i, k)));
+        values.sort();
+        values.dedup();
+        for (i, ranks) in ranks.iter_mut().enumerate() {
+            ranks.push(values.binary_search(&value(i, k)).unwrap() as u32);
+        }
+        max_ranks.push(values.len() as u32 - 1);
+    }
+    let co_occurrences = CoOccurrences { ranks };
+
+    let mut tree = Robin::from(Similarity(&co_occurrences)).pq_tree()?;
+    let mut in_set = vec![false; n];
+    for (k, &max_rank) in max_ranks.iter().enumerate() {
+        for threshold in 1..=max_rank {
+            for (i, in_set) in in_set.iter_mut().enumerate() {
+                *in_set = co_occurrences.ranks[i][k] >= threshold;
+            }
+            tree = tree.reduce(&in_set)?;
+        }
+    }
+    return Some(tree.frontier());
+}
+
+impl<T: Copy> RectangularMatrice<T> {
+    ///Return the number of rows
+    pub fn n_rows(&self) -> usize {
+        return self.rows.len();
+    }
+
+    ///Return the number of columns
+    pub fn n_columns(&self) -> usize {
+        return self.rows[0].len();
+    }
+
+    ///Return the value at `row` and `column`
+    pub fn get(&self, row: usize, column: usize) -> T {
+        return self.rows[row][column];
+    }
+
+    ///Return the matrice as a `Vec<Vec<T>>`, row after row
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        return self.rows.clone();
+    }
+
+    ///Return the matrice with its rows and columns reordered, row `seriation.rows[k]` becoming row `k`
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if the orders don't have as many points as the matrice has rows and columns
+    pub fn reordered(&self, seriation: &Seriation) -> RectangularMatrice<T> {
+        self.check_sizes(seriation);
+        let rows = seriation
+            .rows
+            .iter()
+            .map(|&i| {
+                seriation
+                    .columns
+                    .iter()
+                    .map(|&j| self.rows[i as usize][j as usize])
+                    .collect()
+            })
+            .collect();
+        return RectangularMatrice { rows };
+    }
+
+    fn check_sizes(&self, seriation: &Seriation) {
+        assert_eq!(
+            (seriation.rows.len(), seriation.columns.len()),
+            (self.n_rows(), self.n_columns()),
+            "orders and matrice sizes differ"
+        );
+    }
+}
+
+impl<T: Ord + Copy> RectangularMatrice<T> {
+    ///Return true if every row and every column is unimodal once reordered along `seriation`, false otherwise
+    ///
+    /// Panic :
+    ///
+    /// This function will panic if the orders don't have as many points as the matrice has rows and columns
+    pub fn is_unimodal(&self, seriation: &Seriation) -> bool {
+        self.check_sizes(seriation);
+        let value = |i: u32, j: u32| self.rows[i as usize][j as usize];
+        return seriation
+            .rows
+            .iter()
+            .all(|&i| is_unimodal(seriation.columns.iter().map(|&j| value(i, j))))
+            && seriation
+                .columns
+                .iter()
+                .all(|&j| is_unimodal(seriation.rows.iter().map(|&i| value(i, j))));
+    }
+
+    /// Tries to find orders of the rows and of the columns making every row and every column unimodal.
+    ///
+    /// Rows are ordered independently from columns : every column must be unimodal along the order of the rows, and conversely. The co-occurrences of the rows in the sets of rows reaching a value in a column are a Robinson similarity, solved by `Robin`. Its compatible orders are then restricted to the ones keeping every such set consecutive, which are exactly the orders wanted. The same is done for the columns.
+    ///
+    /// For n rows and m columns, this takes O(n·m·(n + m)) time and O(n·m) memory besides the matrice.
+    ///
+    /// Return `None` if the matrice can't be seriated.
+    pub fn seriate(&self) -> Option<Seriation> {
+        let (n_rows, n_columns) = (self.n_rows(), self.n_columns());
+        let rows = unimodal_order(n_rows, n_columns, |i, k| self.rows[i][k])?;
+        let columns = unimodal_order(n_columns, n_rows, |j, k| self.rows[k][j])?;
+        return Some(Seriation { rows, columns });
+    }
+}
+
+#[test]
+fn test_seriate() {
+    // Contexts in chronological order, using types which appear then disappear
+    let incidences = RectangularMatrice::try_from(vec![
+        vec![1, 1, 0, 0, 0],
+        vec![1, 1, 1, 0, 0],
+        vec![0, 1, 1, 1, 0],
+        vec![0, 0, 1, 1, 1],
+        vec![0, 0, 0, 0, 1],
+    ])
+    .unwrap();
+    let shuffled = incidences.reordered(&Seriation {
+        rows: Permutation::try_from(vec![3, 0, 4, 1, 2]).unwrap(),
+        columns: Permutation::try_from(vec![2, 4, 0, 3, 1]).unwrap(),
+    });
+    let identity = Seriation {
+        rows: Permutation::identity(5),
+        columns: Permutation::identity(5),
+    };
+    assert!(incidences.is_unimodal(&identity));
+    assert!(!shuffled.is_unimodal(&identity));
+    let seriation = shuffled.seriate().unwrap();
+    assert!(shuffled.is_unimodal(&seriation));
+    assert!(shuffled.reordered(&seriation).is_unimodal(&identity));
+
+    // Abundances of 4 terms in 3 documents
+    let abundances =
+        RectangularMatrice::try_from(vec![vec![0, 5, 2, 9], vec![3, 1, 0, 4], vec![1, 6, 3, 8]])
+            .unwrap();
+    let seriation = abundances.seriate().unwrap();
+    assert!(abundances.is_unimodal(&seriation));
+
+    // Three columns, any two of them sharing a row
+    let cycle =
+        RectangularMatrice::try_from(vec![vec![1, 1, 0], vec![0, 1, 1], vec![1, 0, 1]]).unwrap();
+    assert_eq!(cycle.seriate(), None);
+
+    assert_eq!(
+        RectangularMatrice::try_from(vec![vec![1, 0], vec![1]]),
+        Err(MatriceError::NotRectangular {
+            row: 1,
+            len: 1,
+            expected: 2
+        })
+    );
+}
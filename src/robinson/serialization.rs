@@ -0,0 +1,280 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    DistanceMatrice, LabeledMatrice, MmoduleTree, NotRobinson, Permutation, PqNode, PqTree,
+    Storage, Violation,
+};
+
+/// How a `DistanceMatrice` is serialized, keeping its storage : `{"rows": [[...], ...]}` or `{"condensed": [...]}`
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum MatriceRef<'a, T> {
+    Rows(&'a [Vec<T>]),
+    Condensed(&'a [T]),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MatriceRepr<T> {
+    Rows(Vec<Vec<T>>),
+    Condensed(Vec<T>),
+}
+
+impl<T: Serialize> Serialize for DistanceMatrice<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = match &self.storage {
+            Storage::Full(distance_mat) => MatriceRef::Rows(distance_mat),
+            Storage::Condensed { values, .. } => MatriceRef::Condensed(values),
+        };
+        return repr.serialize(serializer);
+    }
+}
+
+/// Rows are checked as `TryFrom<Vec<Vec<T>>>` does, condensed vectors as `from_condensed` does
+impl<'de, T: Ord + Copy + Default + Deserialize<'de>> Deserialize<'de> for DistanceMatrice<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let dist = match MatriceRepr::deserialize(deserializer)? {
+            MatriceRepr::Rows(distance_mat) => DistanceMatrice::try_from(distance_mat),
+            MatriceRepr::Condensed(values) => DistanceMatrice::from_condensed(values),
+        };
+        return dist.map_err(D::Error::custom);
+    }
+}
+
+/// Serialized as its `nodes`, the root first
+impl Serialize for PqTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.nodes().serialize(serializer);
+    }
+}
+
+/// Checks the nodes form a tree rooted at the first one, whose leaves are the points `0..n`
+impl<'de> Deserialize<'de> for PqTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nodes = Vec::<PqNode>::deserialize(deserializer)?;
+        let mut seen = vec![false; nodes.len()];
+        let mut leaves = Vec::new();
+        let mut stack = if nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(id) = stack.pop() {
+            match seen.get_mut(id) {
+                None => return Err(D::Error::custom(format!("no node {id} in the PQ tree"))),
+                Some(true) => {
+                    return Err(D::Error::custom(format!(
+                        "node {id} has several parents in the PQ tree"
+                    )))
+                }
+                Some(seen_node) => *seen_node = true,
+            }
+            match &nodes[id] {
+                PqNode::Leaf(point) => leaves.push(*point),
+                PqNode::P(children) | PqNode::Q(children) if children.is_empty() => {
+                    return Err(D::Error::custom(format!(
+                        "node {id} of the PQ tree has no children"
+                    )))
+                }
+                PqNode::P(children) | PqNode::Q(children) => stack.extend(children),
+            }
+        }
+        if let Some(id) = seen.iter().position(|&seen_node| !seen_node) {
+            return Err(D::Error::custom(format!(
+                "node {id} isn't in the PQ tree rooted at node 0"
+            )));
+        }
+        Permutation::try_from(leaves).map_err(D::Error::custom)?;
+        return Ok(PqTree::from_nodes_unchecked(nodes));
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(
+    rename = "LabeledMatrice",
+    bound(deserialize = "T: Ord + Copy + Default + Deserialize<'de>")
+)]
+struct LabeledMatriceRepr<T> {
+    dist: DistanceMatrice<T>,
+    labels: Option<Vec<String>>,
+}
+
+/// Checks there is one label per point
+impl<'de, T: Ord + Copy + Default + Deserialize<'de>> Deserialize<'de> for LabeledMatrice<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let LabeledMatriceRepr { dist, labels } = LabeledMatriceRepr::deserialize(deserializer)?;
+        if let Some(labels) = &labels {
+            if labels.len() != dist.len() {
+                return Err(D::Error::custom(format!(
+                    "{} labels for a matrice of {} points",
+                    labels.len(),
+                    dist.len()
+                )));
+            }
+        }
+        return Ok(LabeledMatrice { dist, labels });
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "NotRobinson")]
+struct NotRobinsonRepr {
+    permut: Permutation,
+    violation: Violation,
+}
+
+/// Checks the points of the violation are points of the permutation, `neighbour` being right after `i` or right before `j` in it
+impl<'de> Deserialize<'de> for NotRobinson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let NotRobinsonRepr { permut, violation } = NotRobinsonRepr::deserialize(deserializer)?;
+        let Violation { i, neighbour, j } = violation;
+        if [i, neighbour, j]
+            .iter()
+            .any(|&point| point as usize >= permut.len())
+        {
+            return Err(D::Error::custom(format!(
+                "the violation ({i}, {neighbour}, {j}) has points the permutation of {} points doesn't have",
+                permut.len()
+            )));
+        }
+        let position = permut.inverse();
+        let (i_at, neighbour_at, j_at) = (
+            position[i as usize],
+            position[neighbour as usize],
+            position[j as usize],
+        );
+        if i_at >= j_at || (neighbour_at != i_at + 1 && neighbour_at + 1 != j_at) {
+            return Err(D::Error::custom(format!(
+                "{neighbour} isn't next to {i} or {j} between them in the permutation"
+            )));
+        }
+        return Ok(NotRobinson { permut, violation });
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "MmoduleTree")]
+struct MmoduleTreeRepr {
+    pivot: u32,
+    points: Vec<u32>,
+    copoints: Vec<MmoduleTree>,
+}
+
+/// Checks the points of each node are its pivot and the points of its copoints, each once
+impl<'de> Deserialize<'de> for MmoduleTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let MmoduleTreeRepr {
+            pivot,
+            points,
+            copoints,
+        } = MmoduleTreeRepr::deserialize(deserializer)?;
+        let mut expected: Vec<u32> = copoints
+            .iter()
+            .flat_map(|copoint| copoint.points.iter().copied())
+            .chain([pivot])
+            .collect();
+        expected.sort_unstable();
+        let mut sorted = points.clone();
+        sorted.sort_unstable();
+        if sorted != expected || sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(D::Error::custom(format!(
+                "the points of the node of pivot {pivot} aren't its pivot and the points of its copoints, each once"
+            )));
+        }
+        return Ok(MmoduleTree {
+            pivot,
+            points,
+            copoints,
+        });
+    }
+}
+
+#[test]
+fn test_serde_matrice() {
+    let rows = vec![vec![0, 1, 3], vec![1, 0, 2], vec![3, 2, 0]];
+    let dist = DistanceMatrice::try_from(rows.clone()).unwrap();
+    let json = serde_json::to_string(&dist).unwrap();
+    assert_eq!(json, r#"{"rows":[[0,1,3],[1,0,2],[3,2,0]]}"#);
+    let read: DistanceMatrice = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.to_rows(), rows);
+
+    let condensed = dist.into_condensed();
+    let json = serde_json::to_string(&condensed).unwrap();
+    assert_eq!(json, r#"{"condensed":[1,3,2]}"#);
+    let read: DistanceMatrice = serde_json::from_str(&json).unwrap();
+    assert!(read.is_condensed());
+    assert_eq!(read.to_condensed(), vec![1, 3, 2]);
+
+    assert!(serde_json::from_str::<DistanceMatrice>(r#"{"rows":[[0,1],[1]]}"#).is_err());
+    assert!(serde_json::from_str::<DistanceMatrice>(r#"{"condensed":[1,3]}"#).is_err());
+}
+
+#[test]
+fn test_serde_results() {
+    use super::{CompatibleOrder, OrdFloat, Robin};
+
+    let dist = DistanceMatrice::try_from(vec![
+        vec![0, 3, 1, 4],
+        vec![3, 0, 2, 1],
+        vec![1, 2, 0, 3],
+        vec![4, 1, 3, 0],
+    ])
+    .unwrap();
+    let robin = Robin::from(&dist);
+    let order = robin.solve().unwrap();
+    let json = serde_json::to_string(&order).unwrap();
+    assert_eq!(
+        serde_json::from_str::<CompatibleOrder>(&json).unwrap(),
+        order
+    );
+    assert!(serde_json::from_str::<Permutation>("[0, 2, 2]").is_err());
+
+    let tree = robin.mmodule_tree().unwrap();
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(
+        serde_json::from_str::<super::MmoduleTree>(&json).unwrap(),
+        tree
+    );
+
+    let pq_tree = robin.pq_tree().unwrap();
+    let json = serde_json::to_string(&pq_tree).unwrap();
+    assert_eq!(serde_json::from_str::<PqTree>(&json).unwrap(), pq_tree);
+    assert!(serde_json::from_str::<PqTree>(r#"[{"P":[1,1]},{"Leaf":0}]"#).is_err());
+    assert!(serde_json::from_str::<PqTree>(r#"[{"P":[1,2]},{"Leaf":0},{"Leaf":0}]"#).is_err());
+    assert!(serde_json::from_str::<PqTree>(r#"[{"P":[1]},{"Leaf":0},{"Leaf":1}]"#).is_err());
+
+    assert_eq!(serde_json::to_string(&OrdFloat(0.5)).unwrap(), "0.5");
+}
+
+#[test]
+fn test_serde_checks() {
+    let rows = r#"{"rows":[[0,1],[1,0]]}"#;
+    let labeled = |labels: &str| {
+        serde_json::from_str::<LabeledMatrice>(&format!(r#"{{"dist":{rows},"labels":{labels}}}"#))
+    };
+    assert!(labeled("null").is_ok());
+    assert!(labeled(r#"["a","b"]"#).is_ok());
+    assert!(labeled(r#"["a"]"#).is_err());
+    assert!(labeled(r#"["a","b","c"]"#).is_err());
+
+    let not_robinson = |violation: &str| {
+        serde_json::from_str::<NotRobinson>(&format!(
+            r#"{{"permut":[0,1,2,3,4],"violation":{violation}}}"#
+        ))
+    };
+    assert!(not_robinson(r#"{"i":0,"neighbour":1,"j":4}"#).is_ok());
+    assert!(not_robinson(r#"{"i":0,"neighbour":3,"j":4}"#).is_ok());
+    assert!(not_robinson(r#"{"i":0,"neighbour":1,"j":5}"#).is_err());
+    assert!(not_robinson(r#"{"i":0,"neighbour":2,"j":4}"#).is_err());
+    assert!(not_robinson(r#"{"i":4,"neighbour":3,"j":0}"#).is_err());
+
+    let leaf = |point: u32| format!(r#"{{"pivot":{point},"points":[{point}],"copoints":[]}}"#);
+    let tree = |points: &str, copoints: &[String]| {
+        serde_json::from_str::<MmoduleTree>(&format!(
+            r#"{{"pivot":0,"points":{points},"copoints":[{}]}}"#,
+            copoints.join(",")
+        ))
+    };
+    assert!(tree("[1,0,2]", &[leaf(1), leaf(2)]).is_ok());
+    assert!(tree("[0,1]", &[]).is_err());
+    assert!(tree("[1,0]", &[leaf(1), leaf(2)]).is_err());
+    assert!(tree("[1,0,1]", &[leaf(1), leaf(1)]).is_err());
+    assert!(tree("[1,2]", &[leaf(1), leaf(2)]).is_err());
+}
@@ -0,0 +1,225 @@
+use std::cmp::Reverse;
+
+use super::tolerance::Tolerance;
+use super::violations::{measure_violations, violations_of};
+use super::{AbsDiff, Dissimilarity, MeasuredViolation, OrdFloat, RobinsonViolations, Violation};
+
+/// Similarity types with a greatest value, the similarity of each point to itself
+pub trait Greatest: Ord + Copy {
+    const GREATEST: Self;
+}
+
+macro_rules! impl_greatest_int {
+    ($($int:ty),*) => {
+        $(
+            impl Greatest for $int {
+                const GREATEST: Self = <$int>::MAX;
+            }
+        )*
+    };
+}
+
+impl_greatest_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Infinity, only NaN is greater
+impl Greatest for OrdFloat<f32> {
+    const GREATEST: Self = OrdFloat(f32::INFINITY);
+}
+
+/// Infinity, only NaN is greater
+impl Greatest for OrdFloat<f64> {
+    const GREATEST: Self = OrdFloat(f64::INFINITY);
+}
+
+/// Reads a matrice of similarities, where the Robinson property is that values decrease away from the diagonal.
+///
+/// Similarities (co-occurrence counts, correlations, ...) are the distances of `Similarity` in reverse order, so `Robin::from(Similarity(matrice))` finds a compatible order without negating them, unsigned ones included. Its distances are `Reverse<T>`, a tolerance is then given as `Reverse(epsilon)`.
+///
+/// The diagonal of the matrice isn't read : each point is as similar as can be to itself (`Greatest::GREATEST`), so files and condensed matrices can hold anything there. Points of a `Violation` `(i, neighbour, j)` break `s(i, j) <= min(s(i, neighbour), s(neighbour, j))`.
+#[derive(Clone, Copy, Debug)]
+pub struct Similarity<D>(pub D);
+
+impl<D: Dissimilarity> Similarity<D>
+where
+    D::Distance: Greatest,
+{
+    ///Return true if the similarities decrease away from the diagonal, false otherwise
+    pub fn is_robinson(&self) -> bool {
+        return self.find_violation().is_none();
+    }
+
+    ///Return the first triple breaking the Robinson property, `None` if the matrice respects it
+    pub fn find_violation(&self) -> Option<Violation> {
+        return self.find_violation_within(None);
+    }
+
+    ///Return true if the matrice respects the Robinson property up to `epsilon`
+    ///
+    /// Inequalities `s(i, j) <= s(i + 1, j)` and `s(i, j) <= s(i, j - 1)` are only broken if the difference is greater than `epsilon`.
+    pub fn is_robinson_with_tolerance(&self, epsilon: D::Distance) -> bool
+    where
+        D::Distance: AbsDiff,
+    {
+        return self.find_violation_with_tolerance(epsilon).is_none();
+    }
+
+    ///Return the first triple breaking the Robinson property by more than `epsilon`
+    pub fn find_violation_with_tolerance(&self, epsilon: D::Distance) -> Option<Violation>
+    where
+        D::Distance: AbsDiff,
+    {
+        return self.find_violation_within(Some(Tolerance::new(Reverse(epsilon))));
+    }
+
+    fn find_violation_within(
+        &self,
+        tolerance: Option<Tolerance<Reverse<D::Distance>>>,
+    ) -> Option<Violation> {
+        violations_of(self.len() as u32, move |i, j| self.d(i, j), tolerance)
+            .next()
+            .map(|(violation, _, _)| violation)
+    }
+}
+
+impl<D: Dissimilarity> Similarity<D>
+where
+    D::Distance: AbsDiff + Greatest,
+{
+    ///Return every broken inequality of the Robinson property, `magnitude` being how much `s(i, j)` is greater than the similarity to `neighbour`
+    pub fn robinson_violations(&self) -> RobinsonViolations<D::Distance> {
+        return self.collect_violations(None);
+    }
+
+    ///Return every inequality of the Robinson property broken by more than `epsilon`
+    pub fn robinson_violations_with_tolerance(
+        &self,
+        epsilon: D::Distance,
+    ) -> RobinsonViolations<D::Distance> {
+        return self.collect_violations(Some(Tolerance::new(Reverse(epsilon))));
+    }
+
+    fn collect_violations(
+        &self,
+        tolerance: Option<Tolerance<Reverse<D::Distance>>>,
+    ) -> RobinsonViolations<D::Distance> {
+        let reversed = measure_violations(
+            self.len(),
+            violations_of(self.len() as u32, move |i, j| self.d(i, j), tolerance),
+        );
+        return RobinsonViolations {
+            violations: reversed
+                .violations
+                .into_iter()
+                .map(|measured| MeasuredViolation {
+                    violation: measured.violation,
+                    magnitude: measured.magnitude.0,
+                })
+                .collect(),
+            per_row: reversed.per_row,
+        };
+    }
+}
+
+impl<D: Dissimilarity> Dissimilarity for Similarity<D>
+where
+    D::Distance: Greatest,
+{
+    type Distance = Reverse<D::Distance>;
+
+    fn len(&self) -> usize {
+        return self.0.len();
+    }
+
+    /// The least distance on the diagonal, whatever the matrice holds there
+    fn d(&self, i: u32, j: u32) -> Reverse<D::Distance> {
+        if i == j {
+            return Reverse(D::Distance::GREATEST);
+        }
+        return Reverse(self.0.d(i, j));
+    }
+}
+
+#[test]
+fn test_similarity() {
+    use super::{
+        CsvError, CsvFormat, DistanceMatrice, MatriceError, NpzWriter, Permutation, PhylipFormat,
+        Robin,
+    };
+
+    // Co-occurrences of 4 points on a line, in the order 2, 0, 3, 1
+    let counts = DistanceMatrice::try_from(vec![
+        vec![10, 5, 8, 8],
+        vec![5, 10, 1, 8],
+        vec![8, 1, 10, 5],
+        vec![8, 8, 5, 10],
+    ])
+    .unwrap();
+    assert!(!Similarity(&counts).is_robinson());
+
+    let robin = Robin::from(Similarity(&counts));
+    let order = robin.solve().unwrap();
+    assert!(Similarity(counts.permut_matrice(&order.permut)).is_robinson());
+    assert!(Similarity(counts.permuted(&order.permut)).is_robinson());
+
+    let reordered = counts.permut_matrice(&Permutation::try_from(vec![2, 0, 3, 1]).unwrap());
+    assert!(Similarity(&reordered).is_robinson());
+    // Dissimilarities in the same order aren't Robinson
+    assert!(!reordered.is_robinson());
+
+    let noisy =
+        DistanceMatrice::try_from(vec![vec![9, 3, 4], vec![3, 9, 6], vec![4, 6, 9]]).unwrap();
+    let violations = Similarity(&noisy).robinson_violations();
+    assert_eq!(
+        violations.violations,
+        vec![MeasuredViolation {
+            violation: Violation {
+                i: 0,
+                neighbour: 1,
+                j: 2
+            },
+            magnitude: 1
+        }]
+    );
+    assert!(Similarity(&noisy).is_robinson_with_tolerance(1));
+    assert!(!Similarity(&noisy).is_robinson_with_tolerance(0));
+    let robin = Robin::from(Similarity(&noisy)).with_tolerance(Reverse(1));
+    assert!(robin.solve().is_ok());
+
+    // Condensed storage holds zero on the diagonal, which isn't read
+    let condensed = DistanceMatrice::from_condensed(vec![5, 8, 8, 1, 8, 5]).unwrap();
+    assert_eq!(Similarity(&condensed).d(1, 1), Reverse(u32::MAX));
+    let order = Robin::from(Similarity(&condensed)).solve().unwrap();
+    assert!(Similarity(condensed.permuted(&order.permut)).is_robinson());
+    assert!(condensed
+        .permut_matrice(&Permutation::try_from(vec![2, 0, 3, 1]).unwrap())
+        .is_robinson_similarity());
+    assert!(!condensed.is_robinson_similarity());
+
+    // Files of similarities have a nonzero diagonal
+    let format = CsvFormat::csv().with_similarities(true);
+    let read = DistanceMatrice::<u32>::read_csv(
+        "10,5,8,8\n5,10,1,8\n8,1,10,5\n8,8,5,10\n".as_bytes(),
+        &format,
+    )
+    .unwrap();
+    assert_eq!(read.dist.to_condensed(), condensed.to_condensed());
+    assert!(matches!(
+        DistanceMatrice::<u32>::read_csv("1,2\n2,1\n".as_bytes(), &CsvFormat::csv()),
+        Err(CsvError::Matrice(MatriceError::NonZeroDiagonal { i: 0 }))
+    ));
+    let mut written = Vec::new();
+    counts.write_npy(&mut written).unwrap();
+    assert!(DistanceMatrice::<u32>::read_npy(written.as_slice()).is_err());
+    let read = DistanceMatrice::<u32>::read_npy_similarities(written.as_slice()).unwrap();
+    assert!(Robin::from(Similarity(&read)).solve().is_ok());
+    let mut npz = NpzWriter::new(Vec::new());
+    npz.add_matrice("counts", &counts).unwrap();
+    let archive = npz.finish().unwrap();
+    assert!(DistanceMatrice::<u32>::read_npz(archive.as_slice(), None).is_err());
+    let read = DistanceMatrice::<u32>::read_npz_similarities(archive.as_slice(), None).unwrap();
+    assert_eq!(read.to_condensed(), counts.to_condensed());
+    let format = PhylipFormat::relaxed().with_similarities(true);
+    let read =
+        DistanceMatrice::<u32>::read_phylip("2\na 3 1\nb 1 3\n".as_bytes(), &format).unwrap();
+    assert_eq!(read.dist.to_condensed(), vec![1]);
+}
@@ -0,0 +1,67 @@
+/// How a `DistanceMatrice` keeps its distances
+pub(crate) enum Storage<T> {
+    /// Square matrice, only its upper triangle is read
+    Full(Vec<Vec<T>>),
+    /// Upper triangle without the diagonal, row after row, as SciPy's condensed distance vectors. The diagonal is `zero`.
+    Condensed { n: usize, values: Vec<T>, zero: T },
+}
+
+impl<T: Copy> Storage<T> {
+    pub(crate) fn len(&self) -> usize {
+        return match self {
+            Storage::Full(distance_mat) => distance_mat.len(),
+            Storage::Condensed { n, .. } => *n,
+        };
+    }
+
+    /// Distance between `q` and `x`, `q <= x`
+    pub(crate) fn upper(&self, q: usize, x: usize) -> T {
+        return match self {
+            Storage::Full(distance_mat) => distance_mat[q][x],
+            Storage::Condensed { n, values, zero } => {
+                if q == x {
+                    *zero
+                } else {
+                    values[condensed_index(*n, q, x)]
+                }
+            }
+        };
+    }
+
+    /// Line `i` of the matrice, the lower triangle of a condensed matrice is filled with its diagonal value
+    pub(crate) fn line(&self, i: usize) -> Vec<T> {
+        return match self {
+            Storage::Full(distance_mat) => distance_mat[i].clone(),
+            Storage::Condensed { n, zero, .. } => (0..*n)
+                .map(|j| if j < i { *zero } else { self.upper(i, j) })
+                .collect(),
+        };
+    }
+}
+
+/// Position of `d(i, j)` in the condensed vector of `n` points, `i < j`
+pub(crate) fn condensed_index(n: usize, i: usize, j: usize) -> usize {
+    return n * i - i * (i + 1) / 2 + j - i - 1;
+}
+
+/// Number of points `n` such that a condensed vector of `n` points has `len` distances, `n(n - 1) / 2 = len`
+pub(crate) fn condensed_points(len: usize) -> Option<usize> {
+    let estimate = ((1.0 + (1.0 + 8.0 * len as f64).sqrt()) / 2.0) as usize;
+    return (estimate.saturating_sub(1)..=estimate + 1)
+        .find(|&n| n >= 1 && n.checked_mul(n - 1).map(|size| size / 2) == Some(len));
+}
+
+#[test]
+fn test_condensed_points() {
+    assert_eq!(condensed_points(0), Some(1));
+    assert_eq!(condensed_points(1), Some(2));
+    assert_eq!(condensed_points(6), Some(4));
+    assert_eq!(condensed_points(449985000), Some(30000));
+    assert_eq!(condensed_points(5), None);
+
+    let n = 5;
+    let indices: Vec<usize> = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| condensed_index(n, i, j)))
+        .collect();
+    assert_eq!(indices, (0..10).collect::<Vec<usize>>());
+}
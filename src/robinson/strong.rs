@@ -0,0 +1,255 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::tolerance::{cmp_within, Tolerance};
+use super::violations::strict_violations_of;
+use super::{Dissimilarity, DistanceMatrice, Permutation, PermutedView, Robin, Violation};
+
+/// A tie keeping a Robinson order from being strongly Robinson, as found by `DistanceMatrice::find_broken_tie`.
+///
+/// `a` and `b` are consecutive in the order, `near` and `far` lie on the same side of them, `far` further away. `near` doesn't distinguish `a` from `b` (`d(near, a) == d(near, b)`) but `far` does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrokenTie {
+    pub a: u32,
+    pub b: u32,
+    pub near: u32,
+    pub far: u32,
+}
+
+impl fmt::Display for BrokenTie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let BrokenTie { a, b, near, far } = *self;
+        write!(
+            f,
+            "order isn't strongly Robinson : d({near}, {a}) = d({near}, {b}) but d({far}, {a}) != d({far}, {b}) while {far} lies further from them"
+        )
+    }
+}
+
+/// Return the first tie of `n` points in their order which doesn't extend away from the diagonal
+///
+/// For each pair of consecutive points, the points on either side not distinguishing them must be the farthest ones.
+pub(crate) fn find_broken_tie<T: Ord + Copy>(
+    n: u32,
+    d: impl Fn(u32, u32) -> T,
+    tolerance: Option<Tolerance<T>>,
+) -> Option<BrokenTie> {
+    let tie = |x: u32, a: u32, b: u32| cmp_within(tolerance, d(x, a), d(x, b)) == Ordering::Equal;
+    for a in 0..n.saturating_sub(1) {
+        let b = a + 1;
+        // Points before `a` then points after `b`, going away from them
+        for side in [(0..a).rev().collect::<Vec<u32>>(), (b + 1..n).collect()] {
+            let mut near = None;
+            for x in side {
+                match near {
+                    None if tie(x, a, b) => near = Some(x),
+                    Some(near) if !tie(x, a, b) => return Some(BrokenTie { a, b, near, far: x }),
+                    _ => {}
+                }
+            }
+        }
+    }
+    return None;
+}
+
+impl<T: Ord + Copy> DistanceMatrice<T> {
+    ///Return true if the matrice respects the strict Robinson property, false otherwise
+    ///
+    /// For `i < j < k`, `d(i, j) < d(i, k)` and `d(j, k) < d(i, k)` : distances strictly grow away from the diagonal.
+    pub fn is_strictly_robinson(&self) -> bool {
+        return self.find_strict_violation().is_none();
+    }
+
+    ///Return the first triple breaking the strict Robinson property, `None` if the matrice respects it
+    ///
+    /// `neighbour` lies between `i` and `j` but `d(i, j) <= max(d(i, neighbour), d(neighbour, j))`.
+    pub fn find_strict_violation(&self) -> Option<Violation> {
+        return strict_violations_of(self.len() as u32, |i, j| self.d(i, j), None)
+            .next()
+            .map(|(violation, _, _)| violation);
+    }
+
+    ///Return true if the matrice is Robinson and its ties extend away from the diagonal, false otherwise
+    ///
+    /// For `i < j`, `d(i, j) == d(i, j + 1)` implies `d(k, j) == d(k, j + 1)` for every `k < i`, and `d(i, j) == d(i - 1, j)` implies `d(i, k) == d(i - 1, k)` for every `k > j`.
+    pub fn is_strongly_robinson(&self) -> bool {
+        return self.is_robinson() && self.find_broken_tie().is_none();
+    }
+
+    ///Return the first tie breaking the strong Robinson property, `None` if the ties of the matrice extend away from the diagonal
+    ///
+    /// The Robinson property itself isn't checked, see `find_violation`.
+    pub fn find_broken_tie(&self) -> Option<BrokenTie> {
+        return find_broken_tie(self.len() as u32, |i, j| self.d(i, j), None);
+    }
+}
+
+impl<T: Ord + Copy + Default, D: Dissimilarity<Distance = T>> Robin<T, D> {
+    ///Return a strictly Robinson order of the distance matrice, `None` if it doesn't admit any
+    ///
+    /// A strict order makes the farthest pair of every triple unique, so it is the only compatible order up to reversal : any compatible order puts the third point of each triple between that pair, which fixes the middle point of every triple and so the whole order up to reversal. The one `solve` finds is then checked.
+    pub fn strictly_robinson_order(&self) -> Option<Permutation> {
+        let order = self.solve().ok()?.permut;
+        let view = PermutedView::new_unchecked(&self.dist, &order);
+        let strict = strict_violations_of(view.len() as u32, |i, j| view.d(i, j), self.tolerance)
+            .next()
+            .is_none();
+        return strict.then_some(order);
+    }
+
+    ///Return a strongly Robinson order of the distance matrice, `None` if it doesn't admit any
+    ///
+    /// Either every compatible order of a Robinson space is strongly Robinson or none is, the one `solve` finds is checked. Strongly Robinson dissimilarities are the ones given by indexed pyramids, `d(x, y)` being the smallest index of a cluster holding `x` and `y` (Bertrand and Diday, A visual representation of the compatibility between an order and a dissimilarity index : the pyramids, Computational Statistics Quarterly 2, 1985 ; Durand and Fichet, One-to-one correspondences in pyramidal representation : a unified approach, Classification and Related Methods of Data Analysis, 1988). The clusters are the balls, which are intervals of every compatible order, so this doesn't depend on the order. Ties are distances equal up to the tolerance, if one was set.
+    pub fn strongly_robinson_order(&self) -> Option<Permutation> {
+        let order = self.solve().ok()?.permut;
+        let view = PermutedView::new_unchecked(&self.dist, &order);
+        let strong =
+            find_broken_tie(view.len() as u32, |i, j| view.d(i, j), self.tolerance).is_none();
+        return strong.then_some(order);
+    }
+}
+
+#[test]
+fn test_strictly_robinson() {
+    // Points of a line at 0, 1, 3 and 6
+    let line = DistanceMatrice::try_from(vec![
+        vec![0, 1, 3, 6],
+        vec![1, 0, 2, 5],
+        vec![3, 2, 0, 3],
+        vec![6, 5, 3, 0],
+    ])
+    .unwrap();
+    assert!(line.is_strictly_robinson());
+    let shuffled = line.permut_matrice(&Permutation::try_from(vec![2, 0, 3, 1]).unwrap());
+    assert!(!shuffled.is_strictly_robinson());
+    let order = Robin::from(&shuffled).strictly_robinson_order().unwrap();
+    assert!(shuffled.permut_matrice(&order).is_strictly_robinson());
+
+    // Robinson, but 1 and 3 are as far from 0
+    let tied = DistanceMatrice::try_from(vec![
+        vec![0, 1, 3, 3],
+        vec![1, 0, 2, 2],
+        vec![3, 2, 0, 1],
+        vec![3, 2, 1, 0],
+    ])
+    .unwrap();
+    assert!(tied.is_robinson());
+    assert_eq!(
+        tied.find_strict_violation(),
+        Some(Violation {
+            i: 0,
+            neighbour: 2,
+            j: 3
+        })
+    );
+    assert_eq!(Robin::from(&tied).strictly_robinson_order(), None);
+    // Consecutive points may be at distance zero
+    assert!(DistanceMatrice::try_from(vec![vec![0, 0], vec![0, 0]])
+        .unwrap()
+        .is_strictly_robinson());
+}
+
+#[test]
+fn test_strongly_robinson() {
+    // Two clusters {0, 1} and {2, 3}
+    let clusters = DistanceMatrice::try_from(vec![
+        vec![0, 1, 2, 2],
+        vec![1, 0, 2, 2],
+        vec![2, 2, 0, 1],
+        vec![2, 2, 1, 0],
+    ])
+    .unwrap();
+    assert!(clusters.is_strongly_robinson());
+    assert!(Robin::from(&clusters).strongly_robinson_order().is_some());
+
+    // 2 is as far from 0 as from 1, but 3 isn't
+    let broken = DistanceMatrice::try_from(vec![
+        vec![0, 1, 1, 3],
+        vec![1, 0, 1, 2],
+        vec![1, 1, 0, 2],
+        vec![3, 2, 2, 0],
+    ])
+    .unwrap();
+    assert!(broken.is_robinson());
+    assert!(!broken.is_strongly_robinson());
+    assert_eq!(
+        broken.find_broken_tie(),
+        Some(BrokenTie {
+            a: 0,
+            b: 1,
+            near: 2,
+            far: 3
+        })
+    );
+    let shuffled = broken.permut_matrice(&Permutation::try_from(vec![3, 1, 0, 2]).unwrap());
+    assert_eq!(Robin::from(&shuffled).strongly_robinson_order(), None);
+    assert!(Robin::from(&shuffled).solve().is_ok());
+}
+
+#[test]
+fn test_strict_and_strong_orders_brute_force() {
+    use super::pq_tree::next_permutation;
+    let to_permutation = |order: &[usize]| {
+        Permutation::try_from(order.iter().map(|&k| k as u32).collect::<Vec<u32>>()).unwrap()
+    };
+
+    // Small random matrices with many ties, Robinson in a hidden order when the seed is odd
+    let mut state: u64 = 1;
+    let mut next = move |bound: u32| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        return (state >> 33) as u32 % bound;
+    };
+    for seed in 0..600 {
+        let n = 3 + seed % 4;
+        let mut mat = vec![vec![0; n]; n];
+        for gap in 1..n {
+            for i in 0..n - gap {
+                let j = i + gap;
+                mat[i][j] = if seed % 2 == 1 {
+                    mat[i][j - 1].max(mat[i + 1][j]) + next(2)
+                } else {
+                    next(3)
+                };
+            }
+        }
+        let mut hidden_order: Vec<usize> = (0..n).collect();
+        for _ in 0..next(720) {
+            next_permutation(&mut hidden_order);
+        }
+        let dist = DistanceMatrice::try_from(mat)
+            .unwrap()
+            .permut_matrice(&to_permutation(&hidden_order));
+
+        let (mut strict, mut strong, mut compatible) = (0, 0, 0);
+        let mut permut: Vec<usize> = (0..n).collect();
+        loop {
+            let reordered = dist.permut_matrice(&to_permutation(&permut));
+            if reordered.is_robinson() {
+                compatible += 1;
+                strict += reordered.is_strictly_robinson() as usize;
+                strong += reordered.is_strongly_robinson() as usize;
+            }
+            if !next_permutation(&mut permut) {
+                break;
+            }
+        }
+        // Only one strict order up to reversal, every compatible order strongly Robinson or none
+        assert!(strict == 0 || (strict == 2 && compatible == 2));
+        assert!(strong == 0 || strong == compatible);
+
+        let robin = Robin::from(&dist);
+        let order = robin.strictly_robinson_order();
+        assert_eq!(order.is_some(), strict > 0);
+        assert!(order.map_or(true, |order| dist
+            .permut_matrice(&order)
+            .is_strictly_robinson()));
+        let order = robin.strongly_robinson_order();
+        assert_eq!(order.is_some(), strong > 0);
+        assert!(order.map_or(true, |order| dist
+            .permut_matrice(&order)
+            .is_strongly_robinson()));
+    }
+}
@@ -0,0 +1,123 @@
+use std::cmp::{Ordering, Reverse};
+
+use super::OrdFloat;
+
+/// Distance types whose difference can be measured, so they can be compared up to a tolerance.
+pub trait AbsDiff: Copy {
+    /// Return `|self - other|`
+    fn abs_diff(self, other: Self) -> Self;
+
+    /// Return true if `self` and `other` are at most `epsilon` apart
+    fn is_within(self, other: Self, epsilon: Self) -> bool
+    where
+        Self: Ord,
+    {
+        return self.abs_diff(other) <= epsilon;
+    }
+}
+
+macro_rules! impl_abs_diff_unsigned {
+    ($($int:ty),*) => {
+        $(
+            impl AbsDiff for $int {
+                fn abs_diff(self, other: Self) -> Self {
+                    return <$int>::abs_diff(self, other);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_abs_diff_signed {
+    ($($int:ty),*) => {
+        $(
+            impl AbsDiff for $int {
+                /// Saturates at the maximum value if the difference doesn't fit
+                fn abs_diff(self, other: Self) -> Self {
+                    return <$int>::try_from(<$int>::abs_diff(self, other)).unwrap_or(<$int>::MAX);
+                }
+            }
+        )*
+    };
+}
+
+impl_abs_diff_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_abs_diff_signed!(i8, i16, i32, i64, i128, isize);
+
+impl AbsDiff for OrdFloat<f32> {
+    fn abs_diff(self, other: Self) -> Self {
+        return OrdFloat((self.0 - other.0).abs());
+    }
+}
+
+impl AbsDiff for OrdFloat<f64> {
+    fn abs_diff(self, other: Self) -> Self {
+        return OrdFloat((self.0 - other.0).abs());
+    }
+}
+
+/// Distances of a `Similarity`, whose order is reversed but not their differences
+impl<T: Ord + AbsDiff> AbsDiff for Reverse<T> {
+    fn abs_diff(self, other: Self) -> Self {
+        return Reverse(self.0.abs_diff(other.0));
+    }
+
+    fn is_within(self, other: Self, epsilon: Self) -> bool {
+        return self.0.is_within(other.0, epsilon.0);
+    }
+}
+
+/// Compares distances considering the ones less than `epsilon` apart as equal
+#[derive(Clone, Copy)]
+pub(crate) struct Tolerance<T> {
+    epsilon: T,
+    within: fn(T, T, T) -> bool,
+}
+
+impl<T: Ord + AbsDiff> Tolerance<T> {
+    pub(crate) fn new(epsilon: T) -> Tolerance<T> {
+        return Tolerance {
+            epsilon,
+            within: T::is_within,
+        };
+    }
+}
+
+impl<T: Ord + Copy> Tolerance<T> {
+    pub(crate) fn epsilon(&self) -> T {
+        return self.epsilon;
+    }
+
+    pub(crate) fn cmp(&self, a: T, b: T) -> Ordering {
+        if (self.within)(a, b, self.epsilon) {
+            return Ordering::Equal;
+        }
+        return a.cmp(&b);
+    }
+}
+
+/// Compare `a` and `b` exactly if there is no tolerance
+pub(crate) fn cmp_within<T: Ord + Copy>(tolerance: Option<Tolerance<T>>, a: T, b: T) -> Ordering {
+    return match tolerance {
+        Some(tolerance) => tolerance.cmp(a, b),
+        None => a.cmp(&b),
+    };
+}
+
+#[test]
+fn test_tolerance_cmp() {
+    let tolerance = Tolerance::new(OrdFloat(1e-9));
+    assert_eq!(
+        tolerance.cmp(OrdFloat(1.0), OrdFloat(1.0 + 1e-12)),
+        Ordering::Equal
+    );
+    assert_eq!(tolerance.cmp(OrdFloat(1.0), OrdFloat(1.1)), Ordering::Less);
+
+    let tolerance = Tolerance::new(2i32);
+    assert_eq!(tolerance.cmp(-1, 1), Ordering::Equal);
+    assert_eq!(tolerance.cmp(i32::MAX, i32::MIN), Ordering::Greater);
+
+    let tolerance = Tolerance::new(Reverse(2u32));
+    assert_eq!(tolerance.cmp(Reverse(5), Reverse(3)), Ordering::Equal);
+    assert_eq!(tolerance.cmp(Reverse(6), Reverse(3)), Ordering::Less);
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use super::OrdFloat;
+
+/// Why a matrice can't be used as a `DistanceMatrice`. Indices are the ones of the given `Vec<Vec<T>>`, starting at 0.
+///
+/// New variants may be added, matches need a wildcard arm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MatriceError {
+    /// Line `row` has `len` elements instead of `expected`
+    NotSquare {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// Both triangles are filled but `[i][j]` and `[j][i]` differ
+    Asymmetric { i: usize, j: usize },
+    /// `[i][i]` isn't zero (`T::default()`)
+    NonZeroDiagonal { i: usize },
+    /// The matrice has no line
+    Empty,
+    /// The matrice has `len` lines, points are numbered with `u32` so there can't be more than `u32::MAX`
+    TooLarge { len: usize },
+    /// A permutation has `len` points instead of `expected`
+    PermutationLength { len: usize, expected: usize },
+    /// A condensed vector has `len` distances, which isn't `n(n - 1) / 2` for any number of points `n`
+    CondensedLength { len: usize },
+    /// Line `row` of a `RectangularMatrice` has `len` elements instead of `expected`, as many as the first line
+    NotRectangular {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for MatriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MatriceError::NotSquare { row, len, expected } => write!(
+                f,
+                "distance matrice isn't square : line {row} has {len} elements instead of {expected}"
+            ),
+            MatriceError::Asymmetric { i, j } => write!(
+                f,
+                "distance matrice isn't symmetric : [{i}][{j}] and [{j}][{i}] differ"
+            ),
+            MatriceError::NonZeroDiagonal { i } => {
+                write!(f, "distance matrice has a non zero diagonal at [{i}][{i}]")
+            }
+            MatriceError::Empty => write!(f, "distance matrice is empty"),
+            MatriceError::TooLarge { len } => write!(
+                f,
+                "distance matrice has {len} lines, it can't have more than {}",
+                u32::MAX
+            ),
+            MatriceError::PermutationLength { len, expected } => write!(
+                f,
+                "permutation has {len} points instead of {expected}"
+            ),
+            MatriceError::CondensedLength { len } => write!(
+                f,
+                "condensed distance vector has {len} distances, which isn't n(n - 1) / 2 for any number of points n"
+            ),
+            MatriceError::NotRectangular { row, len, expected } => write!(
+                f,
+                "matrice isn't rectangular : line {row} has {len} elements instead of {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatriceError {}
+
+/// Check the matrice has between 1 and `u32::MAX` lines, all as long as the matrice
+pub(crate) fn check_square<T>(distance_mat: &[Vec<T>]) -> Result<(), MatriceError> {
+    let n = distance_mat.len();
+    if n == 0 {
+        return Err(MatriceError::Empty);
+    }
+    if n > u32::MAX as usize {
+        return Err(MatriceError::TooLarge { len: n });
+    }
+    for (row, line) in distance_mat.iter().enumerate() {
+        if line.len() != n {
+            return Err(MatriceError::NotSquare {
+                row,
+                len: line.len(),
+                expected: n,
+            });
+        }
+    }
+    return Ok(());
+}
+
+/// How `DistanceMatrice::symmetrized` computes the distance between two points from the two triangles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetrize {
+    /// Keep the upper triangle, ignore the lower one
+    Upper,
+    /// Keep the lower triangle, ignore the upper one
+    Lower,
+    Min,
+    Max,
+    /// Mean of both triangles, rounded down for integers
+    Mean,
+}
+
+impl Symmetrize {
+    pub(crate) fn apply<T: Ord + Mean>(self, upper: T, lower: T) -> T {
+        return match self {
+            Symmetrize::Upper => upper,
+            Symmetrize::Lower => lower,
+            Symmetrize::Min => upper.min(lower),
+            Symmetrize::Max => upper.max(lower),
+            Symmetrize::Mean => upper.mean(lower),
+        };
+    }
+}
+
+/// Distance types two values can be averaged of, needed by `Symmetrize::Mean`
+pub trait Mean: Copy {
+    fn mean(self, other: Self) -> Self;
+}
+
+macro_rules! impl_mean_int {
+    ($($int:ty),*) => {
+        $(
+            impl Mean for $int {
+                /// Rounded down, without overflowing
+                fn mean(self, other: Self) -> Self {
+                    return (self & other) + ((self ^ other) >> 1);
+                }
+            }
+        )*
+    };
+}
+
+impl_mean_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Mean for OrdFloat<f32> {
+    fn mean(self, other: Self) -> Self {
+        return OrdFloat(self.0 / 2.0 + other.0 / 2.0);
+    }
+}
+
+impl Mean for OrdFloat<f64> {
+    fn mean(self, other: Self) -> Self {
+        return OrdFloat(self.0 / 2.0 + other.0 / 2.0);
+    }
+}
+
+#[test]
+fn test_mean() {
+    assert_eq!(3u32.mean(6), 4);
+    assert_eq!(u8::MAX.mean(u8::MAX - 2), u8::MAX - 1);
+    assert_eq!((-3i32).mean(-6), -5);
+    assert_eq!(OrdFloat(1.0).mean(OrdFloat(2.0)), OrdFloat(1.5));
+}
@@ -0,0 +1,138 @@
+use super::tolerance::Tolerance;
+use super::violations::{measure_violations, violations_of};
+use super::{
+    AbsDiff, Dissimilarity, DistanceMatrice, MatriceError, Permutation, RobinsonViolations,
+    Violation,
+};
+
+/// A `Dissimilarity` with a permutation applied, as returned by `DistanceMatrice::permuted`.
+///
+/// Unlike `permut_matrice`, nothing is copied : `view.d(i, j)` reads `d(permut[i], permut[j])` from the matrice, so checking an order takes no extra memory. Points of the view are positions in the order, as in the matrice `permut_matrice` would return.
+pub struct PermutedView<'a, D = DistanceMatrice> {
+    dist: &'a D,
+    permut: &'a Permutation,
+}
+
+impl<'a, D: Dissimilarity> PermutedView<'a, D> {
+    ///Create a view of `dist` with `permut` applied, or an error if `permut` doesn't have as many points as `dist`
+    ///
+    /// Checks the Robinson property of any `Dissimilarity`, `Permutation::identity` leaving it as it is.
+    pub fn new(dist: &'a D, permut: &'a Permutation) -> Result<PermutedView<'a, D>, MatriceError> {
+        if permut.len() != dist.len() {
+            return Err(MatriceError::PermutationLength {
+                len: permut.len(),
+                expected: dist.len(),
+            });
+        }
+        return Ok(PermutedView { dist, permut });
+    }
+
+    /// `permut` must have as many points as `dist`
+    pub(crate) fn new_unchecked(dist: &'a D, permut: &'a Permutation) -> PermutedView<'a, D> {
+        return PermutedView { dist, permut };
+    }
+
+    ///Return the number of points
+    pub fn len(&self) -> usize {
+        return self.permut.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.permut.is_empty();
+    }
+
+    ///Return the permutation applied to the matrice
+    pub fn permutation(&self) -> &'a Permutation {
+        return self.permut;
+    }
+
+    ///Return the distance between the points at positions `i` and `j` of the order
+    pub fn d(&self, i: u32, j: u32) -> D::Distance {
+        return self
+            .dist
+            .d(self.permut[i as usize], self.permut[j as usize]);
+    }
+
+    ///Return true if the permuted matrice respects the Robinson property, false otherwise
+    pub fn is_robinson(&self) -> bool {
+        return self.find_violation().is_none();
+    }
+
+    ///Return the first triple of the permuted matrice breaking the Robinson property, `None` if it respects it
+    pub fn find_violation(&self) -> Option<Violation> {
+        return self.find_violation_within(None);
+    }
+
+    ///Return true if the permuted matrice respects the Robinson property up to `epsilon`, see `DistanceMatrice::is_robinson_with_tolerance`
+    pub fn is_robinson_with_tolerance(&self, epsilon: D::Distance) -> bool
+    where
+        D::Distance: AbsDiff,
+    {
+        return self.find_violation_with_tolerance(epsilon).is_none();
+    }
+
+    ///Return the first triple of the permuted matrice breaking the Robinson property by more than `epsilon`
+    pub fn find_violation_with_tolerance(&self, epsilon: D::Distance) -> Option<Violation>
+    where
+        D::Distance: AbsDiff,
+    {
+        return self.find_violation_within(Some(Tolerance::new(epsilon)));
+    }
+
+    pub(crate) fn find_violation_within(
+        &self,
+        tolerance: Option<Tolerance<D::Distance>>,
+    ) -> Option<Violation> {
+        return violations_of(self.len() as u32, move |i, j| self.d(i, j), tolerance)
+            .next()
+            .map(|(violation, _, _)| violation);
+    }
+}
+
+impl<D: Dissimilarity> PermutedView<'_, D>
+where
+    D::Distance: AbsDiff,
+{
+    ///Return every broken inequality of the Robinson property in the permuted matrice, see `DistanceMatrice::robinson_violations`
+    pub fn robinson_violations(&self) -> RobinsonViolations<D::Distance> {
+        return self.collect_violations(None);
+    }
+
+    ///Return every inequality of the Robinson property broken by more than `epsilon` in the permuted matrice
+    pub fn robinson_violations_with_tolerance(
+        &self,
+        epsilon: D::Distance,
+    ) -> RobinsonViolations<D::Distance> {
+        return self.collect_violations(Some(Tolerance::new(epsilon)));
+    }
+
+    fn collect_violations(
+        &self,
+        tolerance: Option<Tolerance<D::Distance>>,
+    ) -> RobinsonViolations<D::Distance> {
+        return measure_violations(
+            self.len(),
+            violations_of(self.len() as u32, move |i, j| self.d(i, j), tolerance),
+        );
+    }
+}
+
+impl<T: Ord + Copy + Default> PermutedView<'_, DistanceMatrice<T>> {
+    ///Return the permuted matrice, as `DistanceMatrice::permut_matrice` would
+    pub fn to_matrice(&self) -> DistanceMatrice<T> {
+        return self.dist.permut_matrice(self.permut);
+    }
+}
+
+/// A view is itself a source of distances, `Robin` can run on it
+impl<D: Dissimilarity> Dissimilarity for PermutedView<'_, D> {
+    type Distance = D::Distance;
+
+    fn len(&self) -> usize {
+        return self.permut.len();
+    }
+
+    fn d(&self, i: u32, j: u32) -> D::Distance {
+        return PermutedView::d(self, i, j);
+    }
+}
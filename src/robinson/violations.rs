@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+
+use super::tolerance::{cmp_within, Tolerance};
+use super::{AbsDiff, Violation};
+
+/// Every inequality of the Robinson property broken by a `DistanceMatrice`, see `DistanceMatrice::robinson_violations`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RobinsonViolations<T> {
+    pub violations: Vec<MeasuredViolation<T>>,
+    /// Number of violations in each row (the first one at index 0), a violation at `(i, j)` counts for both rows `i` and `j` of the symmetric matrice
+    pub per_row: Vec<usize>,
+}
+
+/// A broken inequality, `magnitude` being how much `d(i, j)` is less than the distance to `neighbour` on the same row or column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasuredViolation<T> {
+    pub violation: Violation,
+    pub magnitude: T,
+}
+
+impl<T: Ord + Copy> RobinsonViolations<T> {
+    ///Return true if the matrice respects the Robinson property
+    pub fn is_empty(&self) -> bool {
+        return self.violations.is_empty();
+    }
+
+    ///Return the number of broken inequalities
+    pub fn len(&self) -> usize {
+        return self.violations.len();
+    }
+
+    ///Return the largest magnitude of the violations, `None` if there is none
+    pub fn max_magnitude(&self) -> Option<T> {
+        return self
+            .violations
+            .iter()
+            .map(|violation| violation.magnitude)
+            .max();
+    }
+}
+
+/// Iterate over the broken inequalities `d(i, j) >= d(i + 1, j)` then `d(i, j) >= d(i, j - 1)` of `n` points, row by row, along with `d(i, j)` and the distance greater than it
+pub(crate) fn violations_of<T: Ord + Copy>(
+    n: u32,
+    d: impl Fn(u32, u32) -> T + Copy,
+    tolerance: Option<Tolerance<T>>,
+) -> impl Iterator<Item = (Violation, T, T)> {
+    return broken_inequalities(n, d, tolerance, |order| order == Ordering::Less);
+}
+
+/// Iterate over the broken strict inequalities `d(i, j) > d(i + 1, j)` then `d(i, j) > d(i, j - 1)` of `n` points, see `violations_of`
+///
+/// Consecutive points are compared to the diagonal by neither inequality, they may be at distance zero.
+pub(crate) fn strict_violations_of<T: Ord + Copy>(
+    n: u32,
+    d: impl Fn(u32, u32) -> T + Copy,
+    tolerance: Option<Tolerance<T>>,
+) -> impl Iterator<Item = (Violation, T, T)> {
+    return broken_inequalities(n, d, tolerance, |order| order != Ordering::Greater)
+        .filter(|(violation, _, _)| violation.j > violation.i + 1);
+}
+
+/// Iterate over the triples where comparing `d(i, j)` to the distance to its neighbour gives an order `broken` rejects
+fn broken_inequalities<T: Ord + Copy>(
+    n: u32,
+    d: impl Fn(u32, u32) -> T + Copy,
+    tolerance: Option<Tolerance<T>>,
+    broken: fn(Ordering) -> bool,
+) -> impl Iterator<Item = (Violation, T, T)> {
+    return (0..n).flat_map(move |i| {
+        (i + 1..n).flat_map(move |j| {
+            let d_ij = d(i, j);
+            [(i + 1, d(i + 1, j)), (j - 1, d(i, j - 1))]
+                .into_iter()
+                .filter(move |&(_, d_neighbour)| broken(cmp_within(tolerance, d_ij, d_neighbour)))
+                .map(move |(neighbour, d_neighbour)| {
+                    (Violation { i, neighbour, j }, d_ij, d_neighbour)
+                })
+        })
+    });
+}
+
+/// Gather the violations of `n` points given by `violations_of`, with their magnitudes and per row counts
+pub(crate) fn measure_violations<T: Ord + AbsDiff>(
+    n: usize,
+    violations: impl Iterator<Item = (Violation, T, T)>,
+) -> RobinsonViolations<T> {
+    let mut per_row = vec![0; n];
+    let mut measured = Vec::new();
+    for (violation, d_ij, d_neighbour) in violations {
+        per_row[violation.i as usize] += 1;
+        per_row[violation.j as usize] += 1;
+        measured.push(MeasuredViolation {
+            violation,
+            magnitude: d_neighbour.abs_diff(d_ij),
+        });
+    }
+    return RobinsonViolations {
+        violations: measured,
+        per_row,
+    };
+}